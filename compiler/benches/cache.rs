@@ -0,0 +1,21 @@
+use compiler::syntax::lex::{cache::LexResult, Lexer};
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+
+fn cache(c: &mut Criterion) {
+    let body = vec!["$total += $item->price(\"EUR\") * 1.21; // tax"; 10_000].join("\n");
+    let script = format!("<?php\n{}\n", body);
+    let bytes = LexResult::new(Lexer::new(&script)).to_bytes().unwrap();
+
+    let mut group = c.benchmark_group("cache");
+    group.throughput(Throughput::Bytes(script.len() as u64));
+    group.bench_function("fresh lex", |b| {
+        b.iter(|| LexResult::new(Lexer::new(&script)))
+    });
+    group.bench_function("cache load", |b| {
+        b.iter(|| LexResult::from_bytes(&bytes).unwrap())
+    });
+    group.finish();
+}
+
+criterion_group!(benches, cache);
+criterion_main!(benches);
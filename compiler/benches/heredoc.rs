@@ -0,0 +1,17 @@
+use compiler::syntax::lex::Lexer;
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+
+fn heredoc(c: &mut Criterion) {
+    let body = vec!["    a line of the heredoc with $some {$text}"; 10_000].join("\n");
+    let script = format!("<?php\n$a = <<<EOT\n{}\n    EOT;\n", body);
+
+    let mut group = c.benchmark_group("heredoc");
+    group.throughput(Throughput::Bytes(script.len() as u64));
+    group.bench_function("10000 lines", |b| {
+        b.iter(|| Lexer::new(&script).tokenize().unwrap())
+    });
+    group.finish();
+}
+
+criterion_group!(benches, heredoc);
+criterion_main!(benches);
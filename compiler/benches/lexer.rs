@@ -0,0 +1,95 @@
+use std::hint::black_box;
+
+use compiler::syntax::lex::{HtmlMode, Lexer};
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+
+/// A class of 500 methods, mostly keywords, identifiers and variables.
+fn class() -> String {
+    let method = "    /**
+     * Adds the item to the order.
+     */
+    public function addItem{n}(Item $item, int $quantity = 1): static
+    {
+        if ($quantity <= 0 || $item->price() < 0.0) {
+            throw new InvalidArgumentException('invalid quantity');
+        }
+        $this->items[] = ['item' => $item, 'quantity' => $quantity];
+        $this->total += $item->price() * $quantity;
+        return $this;
+    }
+";
+    let methods: String = (0..500)
+        .map(|n| method.replace("{n}", &n.to_string()))
+        .collect();
+    format!("<?php\n\nnamespace App\\Orders;\n\nfinal class Order extends Model implements Countable\n{{\n    private array $items = [];\n\n{methods}}}\n")
+}
+
+/// A template of 2000 rows, mostly inline HTML with short echo tags.
+fn template() -> String {
+    let row = "<tr class=\"row\">\n  <td><?= $row['id'] ?></td>\n  <td><?= htmlspecialchars($row['name']) ?></td>\n</tr>\n";
+    format!(
+        "<table>\n<?php foreach ($rows as $row): ?>\n{}<?php endforeach; ?>\n</table>\n",
+        row.repeat(2000)
+    )
+}
+
+/// A config of 5000 entries, mostly strings and numbers.
+fn config() -> String {
+    let entries: String = (0..5000)
+        .map(|n| {
+            format!(
+                "    'key_{n}' => [\"host\" => \"db{n}.example.com\", 'port' => {}, 'ratio' => 0.{n}, 'dsn' => \"mysql:host=$host;port={n}\"],\n",
+                3306 + n
+            )
+        })
+        .collect();
+    format!("<?php\n\nreturn [\n{entries}];\n")
+}
+
+/// Benchmarks lexing the script, reporting tokens per second, against counting its
+/// whitespace separated words, the least work any lexer must do to read the whole script.
+///
+/// The measurements when this benchmark was added, to compare a run against:
+///
+/// ```text
+/// files/class     lexer  14.5 ms  4.2 Mtokens/s   baseline  0.41 ms
+/// files/template  lexer   9.7 ms  4.7 Mtokens/s   baseline  0.58 ms
+/// files/config    lexer  35.7 ms  4.8 Mtokens/s   baseline  1.40 ms
+/// ```
+fn bench_script(c: &mut Criterion, group: &str, name: &str, script: &str, mode: HtmlMode) {
+    let lex = || Lexer::new(script).html_mode(mode).tokenize().unwrap();
+    let tokens = lex().len();
+
+    let mut group = c.benchmark_group(group);
+    group.throughput(Throughput::Elements(tokens as u64));
+    group.bench_function(format!("{name}/lexer"), |b| b.iter(lex));
+    group.bench_function(format!("{name}/baseline"), |b| {
+        b.iter(|| black_box(script).split_whitespace().count())
+    });
+    group.finish();
+}
+
+fn files(c: &mut Criterion) {
+    bench_script(c, "files", "class", &class(), HtmlMode::Code);
+    bench_script(c, "files", "template", &template(), HtmlMode::Opaque);
+    bench_script(c, "files", "config", &config(), HtmlMode::Code);
+}
+
+/// Each hot path of the lexer, in a script of 10000 tokens of the same kind separated by
+/// whitespace.
+fn hot_paths(c: &mut Criterion) {
+    let paths = [
+        ("whitespace", ";\n  \t  "),
+        ("identifiers", "some_identifier "),
+        ("keywords", "function "),
+        ("numbers", "1234.5e6 "),
+        ("strings", "'a string with \\'escapes\\'' "),
+    ];
+    for (name, token) in paths {
+        let script = format!("<?php {}", token.repeat(10_000));
+        bench_script(c, "hot paths", name, &script, HtmlMode::Code);
+    }
+}
+
+criterion_group!(benches, files, hot_paths);
+criterion_main!(benches);
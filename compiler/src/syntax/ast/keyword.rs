@@ -1,4 +1,4 @@
-use std::str::FromStr;
+use std::{borrow::Cow, collections::HashMap, str::FromStr};
 
 pub const MAX_KEYWORD_LENGTH: usize = 11;
 
@@ -8,7 +8,11 @@ pub const MAX_KEYWORD_LENGTH: usize = 11;
 /// ! as reserved calls!
 ///
 /// For more information regarding reserved keywords, visit: https://www.php.net/manual/en/reserved.keywords.php
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize, bincode::Encode, bincode::Decode)
+)]
 pub enum Keyword {
     /// Abstract, used to define "interface" classes and
     /// are not stand-alone initializable.
@@ -73,6 +77,8 @@ pub enum Keyword {
     ///         break;
     /// }
     /// ```
+    ///
+    /// The same keyword declares the cases of an `enum`, eg: `case Hearts = 'H';`.
     Case,
 
     /// Catch, Used to handle exceptions in the current call stack. If no catch block is found,
@@ -174,6 +180,15 @@ pub enum Keyword {
     /// Functions identically to EndDeclare.
     EndWhile,
 
+    /// Enum, declares an enumeration, a type with a closed set of `case`s. Since PHP 8.1.
+    /// A backed enum declares the type of its case values after its name:
+    /// ```php
+    /// enum Suit: string {
+    ///     case Hearts = 'H';
+    /// }
+    /// ```
+    Enum,
+
     /// Extends, A keyword used to "extend", "derive", or "inherit" the methods, constants and properties
     /// from the label following this keyword. For instance, `Foo` would extend `Bar` if `Foo extends Bar`
     /// is written.
@@ -249,6 +264,10 @@ pub enum Keyword {
 
     Or,
 
+    /// Print, a language construct that outputs its argument. Unlike `echo` it takes a single
+    /// argument and is an expression, always evaluating to `1`.
+    Print,
+
     Private,
 
     Protected,
@@ -279,13 +298,46 @@ pub enum Keyword {
 
     While,
 
+    /// Xor, used for logical OP, true when exactly one of its operands is. Like `and` and `or`
+    /// it binds looser than assignment, eg: `$a = true xor true` assigns `true`.
+    Xor,
+
     Yield,
 
     /// Use in `yield from` for generators.
     From,
+
+    /// Get, declares the `get` hook of a property, eg: `public int $x { get => 1; }`.
+    /// Since PHP 8.4, this is only a keyword within the hooks of a property.
+    Get,
+
+    /// Set, declares the `set` hook of a property, eg: `public int $x { set => $value; }`.
+    /// Since PHP 8.4, this is only a keyword within the hooks of a property.
+    Set,
 }
 
 impl Keyword {
+    /// Whether the keyword is fully reserved, so it can never be the name of a class, function
+    /// or constant, eg: `class`. This is the opposite of `is_contextual`.
+    pub fn is_reserved(self) -> bool {
+        !self.is_contextual()
+    }
+
+    /// Whether the keyword is only reserved in some versions or contexts, so a parser may
+    /// accept it as a name, eg: `enum` before PHP 8.1, `readonly` as a function name, or a
+    /// `from` that does not follow `yield`.
+    pub fn is_contextual(self) -> bool {
+        matches!(
+            self,
+            Keyword::From
+                | Keyword::Match
+                | Keyword::Enum
+                | Keyword::ReadOnly
+                | Keyword::Get
+                | Keyword::Set
+        )
+    }
+
     /// Gets the keyword as a string.
     pub fn as_str(self) -> &'static str {
         match self {
@@ -312,6 +364,7 @@ impl Keyword {
             Keyword::EndIf => "endif",
             Keyword::EndSwitch => "endswitch",
             Keyword::EndWhile => "endwhile",
+            Keyword::Enum => "enum",
             Keyword::Extends => "extends",
             Keyword::Final => "final",
             Keyword::Finally => "finally",
@@ -332,6 +385,7 @@ impl Keyword {
             Keyword::Namespace => "namespace",
             Keyword::New => "new",
             Keyword::Or => "or",
+            Keyword::Print => "print",
             Keyword::Private => "private",
             Keyword::Protected => "protected",
             Keyword::Public => "public",
@@ -347,8 +401,11 @@ impl Keyword {
             Keyword::Use => "use",
             Keyword::Var => "var",
             Keyword::While => "while",
+            Keyword::Xor => "xor",
             Keyword::Yield => "yield",
             Keyword::From => "from",
+            Keyword::Get => "get",
+            Keyword::Set => "set",
         }
     }
 }
@@ -384,6 +441,7 @@ impl FromStr for Keyword {
             "endif" => Ok(Self::EndIf),
             "endswitch" => Ok(Self::EndSwitch),
             "endwhile" => Ok(Self::EndWhile),
+            "enum" => Ok(Self::Enum),
             "extends" => Ok(Self::Extends),
             "final" => Ok(Self::Final),
             "finally" => Ok(Self::Finally),
@@ -404,6 +462,7 @@ impl FromStr for Keyword {
             "namespace" => Ok(Self::Namespace),
             "new" => Ok(Self::New),
             "or" => Ok(Self::Or),
+            "print" => Ok(Self::Print),
             "private" => Ok(Self::Private),
             "protected" => Ok(Self::Protected),
             "public" => Ok(Self::Public),
@@ -419,8 +478,10 @@ impl FromStr for Keyword {
             "use" => Ok(Self::Use),
             "var" => Ok(Self::Var),
             "while" => Ok(Self::While),
+            "xor" => Ok(Self::Xor),
             "yield" => Ok(Self::Yield),
             "from" => Ok(Self::From),
+            // `get` and `set` are left out, as the lexer decides whether they are hooks.
             _ => Err(KeywordErr),
         }
     }
@@ -431,3 +492,86 @@ impl std::fmt::Display for Keyword {
         write!(f, "{}", self.as_str())
     }
 }
+
+/// The words lexed as keywords, for lexing dialects of PHP that add or remove keywords.
+/// Words are matched case insensitively, any word not overridden is looked up in the
+/// built-in `Keyword` table, so the default set is standard PHP.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeywordSet {
+    /// Lowercase words mapped to the keyword they lex as, `None` if they lex as identifiers.
+    overrides: HashMap<String, Option<Keyword>>,
+    /// The length in chars of the longest word that may lex as a keyword.
+    max_len: usize,
+}
+
+impl Default for KeywordSet {
+    fn default() -> Self {
+        Self {
+            overrides: HashMap::new(),
+            max_len: MAX_KEYWORD_LENGTH,
+        }
+    }
+}
+
+impl KeywordSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Lexes `word` as the given keyword, eg: a dialect spelling `await` as `wait`.
+    /// Panics if `word` is not an identifier, eg: when it is empty.
+    pub fn with(self, word: &str, keyword: Keyword) -> Self {
+        self.set(word, Some(keyword))
+    }
+
+    /// Lexes `word` as an identifier, even if it is a built-in keyword.
+    /// Panics if `word` is not an identifier, eg: when it is empty.
+    pub fn without(self, word: &str) -> Self {
+        self.set(word, None)
+    }
+
+    fn set(mut self, word: &str, keyword: Option<Keyword>) -> Self {
+        assert!(
+            is_identifier(word),
+            "keyword {:?} is not an identifier",
+            word
+        );
+        self.overrides.insert(word.to_ascii_lowercase(), keyword);
+        self.max_len = self
+            .overrides
+            .iter()
+            .filter(|(_, keyword)| keyword.is_some())
+            .map(|(word, _)| word.chars().count())
+            .fold(MAX_KEYWORD_LENGTH, usize::max);
+        self
+    }
+
+    /// The keyword `word` lexes as, if any.
+    pub fn get(&self, word: &str) -> Option<Keyword> {
+        // most words are already lowercase, so they are looked up without being copied.
+        let word = if word.bytes().any(|b| b.is_ascii_uppercase()) {
+            Cow::Owned(word.to_ascii_lowercase())
+        } else {
+            Cow::Borrowed(word)
+        };
+        match self.overrides.get(word.as_ref()) {
+            Some(keyword) => *keyword,
+            None => Keyword::from_str(&word).ok(),
+        }
+    }
+
+    /// The length in chars of the longest word that may lex as a keyword.
+    pub fn max_len(&self) -> usize {
+        self.max_len
+    }
+}
+
+/// Whether the word is a PHP identifier, whose chars are `_`, alphanumeric or non ASCII and
+/// that does not start with a digit.
+fn is_identifier(word: &str) -> bool {
+    let part = |c: char| c == '_' || c.is_alphanumeric() || !c.is_ascii();
+    word.chars()
+        .next()
+        .is_some_and(|c| part(c) && !c.is_ascii_digit())
+        && word.chars().all(part)
+}
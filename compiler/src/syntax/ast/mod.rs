@@ -1,2 +1,3 @@
 pub mod keyword;
+pub mod operator;
 pub mod reserved;
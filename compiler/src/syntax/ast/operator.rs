@@ -0,0 +1,289 @@
+use std::str::FromStr;
+
+/// The operators that may be lexed as an `Operator` token.
+/// The word operators `and`, `or` and `xor` are lexed as keywords instead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OperatorKind {
+    /// `+`
+    Add,
+    /// `-`
+    Sub,
+    /// `*`
+    Mul,
+    /// `/`
+    Div,
+    /// `%`
+    Mod,
+    /// `**`
+    Pow,
+    /// `<<`
+    ShiftLeft,
+    /// `>>`
+    ShiftRight,
+    /// `=`, there is no `=&` operator, a reference assignment such as `$a =& $b` is always
+    /// lexed as `=` followed by `&`, the same as `$a = &$b`.
+    Assign,
+    /// `+=`
+    AddAssign,
+    /// `-=`
+    SubAssign,
+    /// `*=`
+    MulAssign,
+    /// `/=`
+    DivAssign,
+    /// `%=`
+    ModAssign,
+    /// `**=`
+    PowAssign,
+    /// `.=`
+    ConcatAssign,
+    /// `&=`
+    BitAndAssign,
+    /// `|=`
+    BitOrAssign,
+    /// `^=`
+    BitXorAssign,
+    /// `<<=`
+    ShiftLeftAssign,
+    /// `>>=`
+    ShiftRightAssign,
+    /// `??`, evaluates to the right hand when the left hand is `null` or not set.
+    Coalesce,
+    /// `??=`
+    CoalesceAssign,
+    /// `==`
+    Equal,
+    /// `!=`, also written as `<>`.
+    NotEqual,
+    /// `===`
+    Identical,
+    /// `!==`
+    NotIdentical,
+    /// `<`
+    Less,
+    /// `<=`
+    LessEqual,
+    /// `>`
+    Greater,
+    /// `>=`
+    GreaterEqual,
+    /// `<=>`, compares two values, evaluating to `-1`, `0` or `1`.
+    /// Commonly used within sort callbacks, eg: `fn ($a, $b) => $a <=> $b`.
+    Spaceship,
+    /// `!`
+    Not,
+    /// `&`, this is also used to take a reference, eg: `&$a`.
+    BitAnd,
+    /// `|`
+    BitOr,
+    /// `^`
+    BitXor,
+    /// `~`
+    BitNot,
+    /// `@`, suppresses errors raised by the expression it prefixes.
+    Silence,
+    /// `&&`
+    BooleanAnd,
+    /// `||`
+    BooleanOr,
+    /// `=>`, separates keys from values in arrays, match arms and arrow function bodies.
+    DoubleArrow,
+}
+
+impl OperatorKind {
+    /// Gets the operator as a string.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OperatorKind::Add => "+",
+            OperatorKind::Sub => "-",
+            OperatorKind::Mul => "*",
+            OperatorKind::Div => "/",
+            OperatorKind::Mod => "%",
+            OperatorKind::Pow => "**",
+            OperatorKind::ShiftLeft => "<<",
+            OperatorKind::ShiftRight => ">>",
+            OperatorKind::Assign => "=",
+            OperatorKind::AddAssign => "+=",
+            OperatorKind::SubAssign => "-=",
+            OperatorKind::MulAssign => "*=",
+            OperatorKind::DivAssign => "/=",
+            OperatorKind::ModAssign => "%=",
+            OperatorKind::PowAssign => "**=",
+            OperatorKind::ConcatAssign => ".=",
+            OperatorKind::BitAndAssign => "&=",
+            OperatorKind::BitOrAssign => "|=",
+            OperatorKind::BitXorAssign => "^=",
+            OperatorKind::ShiftLeftAssign => "<<=",
+            OperatorKind::ShiftRightAssign => ">>=",
+            OperatorKind::Coalesce => "??",
+            OperatorKind::CoalesceAssign => "??=",
+            OperatorKind::Equal => "==",
+            OperatorKind::NotEqual => "!=",
+            OperatorKind::Identical => "===",
+            OperatorKind::NotIdentical => "!==",
+            OperatorKind::Less => "<",
+            OperatorKind::LessEqual => "<=",
+            OperatorKind::Greater => ">",
+            OperatorKind::GreaterEqual => ">=",
+            OperatorKind::Spaceship => "<=>",
+            OperatorKind::Not => "!",
+            OperatorKind::BitAnd => "&",
+            OperatorKind::BitOr => "|",
+            OperatorKind::BitXor => "^",
+            OperatorKind::BitNot => "~",
+            OperatorKind::Silence => "@",
+            OperatorKind::BooleanAnd => "&&",
+            OperatorKind::BooleanOr => "||",
+            OperatorKind::DoubleArrow => "=>",
+        }
+    }
+
+    /// How tightly the operator binds its operands, operators with a higher precedence bind
+    /// before those with a lower one. `=>` is not an operation, so it has no precedence.
+    ///
+    /// Operators such as `-` and `&` are ranked as binary operators, see:
+    /// https://www.php.net/manual/en/language.operators.precedence.php
+    pub fn precedence(&self) -> Option<u8> {
+        Some(match self {
+            OperatorKind::Pow => 21,
+            OperatorKind::BitNot | OperatorKind::Silence => 20,
+            OperatorKind::Not => 18,
+            OperatorKind::Mul | OperatorKind::Div | OperatorKind::Mod => 17,
+            OperatorKind::Add | OperatorKind::Sub => 16,
+            OperatorKind::ShiftLeft | OperatorKind::ShiftRight => 15,
+            OperatorKind::Less
+            | OperatorKind::LessEqual
+            | OperatorKind::Greater
+            | OperatorKind::GreaterEqual => 13,
+            OperatorKind::Equal
+            | OperatorKind::NotEqual
+            | OperatorKind::Identical
+            | OperatorKind::NotIdentical
+            | OperatorKind::Spaceship => 12,
+            OperatorKind::BitAnd => 11,
+            OperatorKind::BitXor => 10,
+            OperatorKind::BitOr => 9,
+            OperatorKind::BooleanAnd => 8,
+            OperatorKind::BooleanOr => 7,
+            OperatorKind::Coalesce => 6,
+            OperatorKind::Assign
+            | OperatorKind::AddAssign
+            | OperatorKind::SubAssign
+            | OperatorKind::MulAssign
+            | OperatorKind::DivAssign
+            | OperatorKind::ModAssign
+            | OperatorKind::PowAssign
+            | OperatorKind::ConcatAssign
+            | OperatorKind::BitAndAssign
+            | OperatorKind::BitOrAssign
+            | OperatorKind::BitXorAssign
+            | OperatorKind::ShiftLeftAssign
+            | OperatorKind::ShiftRightAssign
+            | OperatorKind::CoalesceAssign => 4,
+            OperatorKind::DoubleArrow => return None,
+        })
+    }
+
+    /// How many operands the operator takes, eg: `!` only prefixes an operand while `-` may also
+    /// be written between two, as in `-$a` and `$a - $b`.
+    pub fn arity(&self) -> Arity {
+        match self {
+            OperatorKind::Not | OperatorKind::BitNot | OperatorKind::Silence => Arity::Unary,
+            OperatorKind::Add | OperatorKind::Sub | OperatorKind::BitAnd => Arity::Both,
+            _ => Arity::Binary,
+        }
+    }
+
+    /// Whether the operator assigns to its left hand, eg: `=`, `.=` or `??=`.
+    pub fn is_assignment(&self) -> bool {
+        matches!(
+            self,
+            OperatorKind::Assign
+                | OperatorKind::AddAssign
+                | OperatorKind::SubAssign
+                | OperatorKind::MulAssign
+                | OperatorKind::DivAssign
+                | OperatorKind::ModAssign
+                | OperatorKind::PowAssign
+                | OperatorKind::ConcatAssign
+                | OperatorKind::BitAndAssign
+                | OperatorKind::BitOrAssign
+                | OperatorKind::BitXorAssign
+                | OperatorKind::ShiftLeftAssign
+                | OperatorKind::ShiftRightAssign
+                | OperatorKind::CoalesceAssign
+        )
+    }
+}
+
+/// How many operands an operator takes, see `OperatorKind::arity`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Arity {
+    /// A prefix of a single operand, eg: `!$a`.
+    Unary,
+    /// Written between two operands, eg: `$a * $b`.
+    Binary,
+    /// Either unary or binary, depending on whether it follows an operand, eg: `-$a` or `$a - $b`.
+    /// `&` is unary when taking a reference, eg: `&$a`.
+    Both,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct OperatorErr;
+
+impl FromStr for OperatorKind {
+    type Err = OperatorErr;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "+" => Ok(Self::Add),
+            "-" => Ok(Self::Sub),
+            "*" => Ok(Self::Mul),
+            "/" => Ok(Self::Div),
+            "%" => Ok(Self::Mod),
+            "**" => Ok(Self::Pow),
+            "<<" => Ok(Self::ShiftLeft),
+            ">>" => Ok(Self::ShiftRight),
+            "=" => Ok(Self::Assign),
+            "+=" => Ok(Self::AddAssign),
+            "-=" => Ok(Self::SubAssign),
+            "*=" => Ok(Self::MulAssign),
+            "/=" => Ok(Self::DivAssign),
+            "%=" => Ok(Self::ModAssign),
+            "**=" => Ok(Self::PowAssign),
+            ".=" => Ok(Self::ConcatAssign),
+            "&=" => Ok(Self::BitAndAssign),
+            "|=" => Ok(Self::BitOrAssign),
+            "^=" => Ok(Self::BitXorAssign),
+            "<<=" => Ok(Self::ShiftLeftAssign),
+            ">>=" => Ok(Self::ShiftRightAssign),
+            "??" => Ok(Self::Coalesce),
+            "??=" => Ok(Self::CoalesceAssign),
+            "==" => Ok(Self::Equal),
+            "!=" | "<>" => Ok(Self::NotEqual),
+            "===" => Ok(Self::Identical),
+            "!==" => Ok(Self::NotIdentical),
+            "<" => Ok(Self::Less),
+            "<=" => Ok(Self::LessEqual),
+            ">" => Ok(Self::Greater),
+            ">=" => Ok(Self::GreaterEqual),
+            "<=>" => Ok(Self::Spaceship),
+            "!" => Ok(Self::Not),
+            "&" => Ok(Self::BitAnd),
+            "|" => Ok(Self::BitOr),
+            "^" => Ok(Self::BitXor),
+            "~" => Ok(Self::BitNot),
+            "@" => Ok(Self::Silence),
+            "&&" => Ok(Self::BooleanAnd),
+            "||" => Ok(Self::BooleanOr),
+            "=>" => Ok(Self::DoubleArrow),
+            _ => Err(OperatorErr),
+        }
+    }
+}
+
+impl std::fmt::Display for OperatorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
@@ -1,7 +1,11 @@
 /// The reserved constants or identifiers that php does NOT allow users to define themselves.
 /// Please not that NONE of the `E_*` constants are supported with this compiler.
 /// To retrieve these constants use the `\ErrorConstants::E_*` respectively.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize, bincode::Encode, bincode::Decode)
+)]
 pub enum ReservedIdent {
     PhpVersion,
     PhpMajorVersion,
@@ -50,7 +54,61 @@ pub enum ReservedIdent {
     MagicTrait,
 }
 
-#[derive(Debug, Clone)]
+impl ReservedIdent {
+    /// Gets the reserved word as it is written in a script.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReservedIdent::PhpVersion => "PHP_VERSION",
+            ReservedIdent::PhpMajorVersion => "PHP_MAJOR_VERSION",
+            ReservedIdent::PhpMinorVersion => "PHP_MINOR_VERSION",
+            ReservedIdent::PhpReleaseVersion => "PHP_RELEASE_VERSION",
+            ReservedIdent::PhpVersionId => "PHP_VERSION_ID",
+            ReservedIdent::PhpExtraVersion => "PHP_EXTRA_VERSION",
+            ReservedIdent::PhpZts => "PHP_ZTS",
+            ReservedIdent::PhpDebug => "PHP_DEBUG",
+            ReservedIdent::PhpMaxPathLen => "PHP_MAXPATHLEN",
+            ReservedIdent::PhpOs => "PHP_OS",
+            ReservedIdent::PhpOsFamily => "PHP_OS_FAMILY",
+            ReservedIdent::PhpSapi => "PHP_SAPI",
+            ReservedIdent::PhpEol => "PHP_EOL",
+            ReservedIdent::PhpIntMax => "PHP_INT_MAX",
+            ReservedIdent::PhpIntMin => "PHP_INT_MIN",
+            ReservedIdent::PhpFloatDig => "PHP_FLOAT_DIG",
+            ReservedIdent::PhpFloatEpsilon => "PHP_FLOAT_EPSILON",
+            ReservedIdent::PhpFloatMin => "PHP_FLOAT_MIN",
+            ReservedIdent::PhpFloatMax => "PHP_FLOAT_MAX",
+            ReservedIdent::DefaultIncludePath => "DEFAULT_INCLUDE_PATH",
+            ReservedIdent::PearInstallDir => "PEAR_INSTALL_DIR",
+            ReservedIdent::PearExtensionDir => "PEAR_EXTENSION_DIR",
+            ReservedIdent::PhpExtensionDir => "PHP_EXTENSION_DIR",
+            ReservedIdent::PhpPrefix => "PHP_PREFIX",
+            ReservedIdent::PhpBinDir => "PHP_BINDIR",
+            ReservedIdent::PhpBinary => "PHP_BINARY",
+            ReservedIdent::PhpManDir => "PHP_MANDIR",
+            ReservedIdent::PhpLibDir => "PHP_LIBDIR",
+            ReservedIdent::PhpDataDir => "PHP_DATADIR",
+            ReservedIdent::PhpLocaleStateDir => "PHP_LOCALSTATEDIR",
+            ReservedIdent::PhpConfigFilePath => "PHP_CONFIG_FILE_PATH",
+            ReservedIdent::PhpConfigFileScanDir => "PHP_CONFIG_FILE_SCAN_DIR",
+            ReservedIdent::PhpShLibSuffix => "PHP_SHLIB_SUFFIX",
+            ReservedIdent::PhpFdSetSize => "PHP_FD_SETSIZE",
+            ReservedIdent::MagicClass => "__CLASS__",
+            ReservedIdent::MagicDir => "__DIR__",
+            ReservedIdent::MagicFile => "__FILE__",
+            ReservedIdent::MagicFunction => "__FUNCTION__",
+            ReservedIdent::MagicLine => "__LINE__",
+            ReservedIdent::MagicMethod => "__METHOD__",
+            ReservedIdent::MagicNamespace => "__NAMESPACE__",
+            ReservedIdent::MagicTrait => "__TRAIT__",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize, bincode::Encode, bincode::Decode)
+)]
 pub enum ReservedCall {
     /// Represents the `__halt_compiler()` function
     HaltCompiler,
@@ -64,3 +122,20 @@ pub enum ReservedCall {
     List,
     Unset,
 }
+
+impl ReservedCall {
+    /// Gets the reserved word as it is written in a script.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReservedCall::HaltCompiler => "__halt_compiler",
+            ReservedCall::Array => "array",
+            ReservedCall::Die => "die",
+            ReservedCall::Empty => "empty",
+            ReservedCall::Eval => "eval",
+            ReservedCall::Exit => "exit",
+            ReservedCall::IsSet => "isset",
+            ReservedCall::List => "list",
+            ReservedCall::Unset => "unset",
+        }
+    }
+}
@@ -0,0 +1,217 @@
+use std::{collections::HashMap, io::Error};
+
+use crate::syntax::ast::keyword::Keyword;
+
+use super::{
+    span::Span,
+    token::{Token, TokenKind, TokenType},
+    Lexer,
+};
+
+/// Statistics collected while lexing, see `Lexer::collect_stats`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize, bincode::Encode, bincode::Decode)
+)]
+pub struct LexStats {
+    /// The amount of tokens lexed, including whitespace and comments.
+    pub tokens: usize,
+    /// The most brackets, parenthesis and braces open at once.
+    pub max_depth: usize,
+    /// The amount of string literals, including heredocs and nowdocs.
+    pub strings: usize,
+    pub comments: usize,
+    /// The length of the longest identifier, in chars.
+    pub longest_identifier: usize,
+}
+
+impl LexStats {
+    /// Records a token that was just lexed, while `depth` brackets were open.
+    pub(super) fn record(&mut self, token: &Token, depth: usize) {
+        self.tokens += 1;
+        self.max_depth = self.max_depth.max(depth);
+        match &token.0 {
+            TokenType::StringLit(_) => self.strings += 1,
+            TokenType::Comment => self.comments += 1,
+            TokenType::Identifier => {
+                let len = token.2.as_deref().map_or(0, |name| name.chars().count());
+                self.longest_identifier = self.longest_identifier.max(len);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Counts the tokens of each kind, eg: how many keywords or variables a script has.
+pub fn token_histogram(tokens: &[Token]) -> HashMap<TokenKind, usize> {
+    let mut histogram = HashMap::new();
+    for token in tokens {
+        *histogram.entry(token.0.kind()).or_insert(0) += 1;
+    }
+    histogram
+}
+
+/// Counts each keyword, eg: how many `function`s a script declares.
+pub fn keyword_histogram(tokens: &[Token]) -> HashMap<Keyword, usize> {
+    let mut histogram = HashMap::new();
+    for token in tokens {
+        if let TokenType::Keyword(keyword) = token.0 {
+            *histogram.entry(keyword).or_insert(0) += 1;
+        }
+    }
+    histogram
+}
+
+/// Whether any content of the script appears outside of its `<?php ... ?>` tags, eg: a
+/// template mixing HTML with PHP. A file without any tags is all HTML, so it is mixed.
+///
+/// This is a quick scan for tags rather than a full lex, so a `?>` within a string or comment
+/// is taken as a close tag. A leading shebang line and the single line break following a
+/// close tag are not content, as PHP does not output them.
+pub fn is_mixed_template(script: &str) -> bool {
+    let mut rest = script;
+    if rest.starts_with("#!") {
+        rest = rest.find('\n').map_or("", |end| &rest[end + 1..]);
+    }
+
+    loop {
+        let Some(open) = rest.find("<?") else {
+            return !rest.is_empty();
+        };
+        if open > 0 {
+            return true;
+        }
+
+        let Some(close) = rest.find("?>") else {
+            return false;
+        };
+        rest = &rest[close + 2..];
+        rest = rest
+            .strip_prefix("\r\n")
+            .or_else(|| rest.strip_prefix('\n'))
+            .unwrap_or(rest);
+    }
+}
+
+/// A name found within a script by `extract_symbols`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Symbol {
+    /// The name, without the `$` of a variable.
+    pub name: String,
+    pub kind: SymbolKind,
+    /// The span of the name, including the `$` of a variable.
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SymbolKind {
+    /// A variable, eg: `$x`.
+    Variable,
+    /// The name a function or method is declared with, eg: `foo` in `function foo()`.
+    Function,
+    /// The name a class, interface, trait or enum is declared with.
+    Class,
+    /// Any other identifier, eg: the name of a called function or a constant.
+    Identifier,
+}
+
+/// Lexes the script, returning every identifier and variable name in the order they appear.
+/// Declared names are told apart by the keyword preceding them, eg: `class Foo` is a `Class`.
+pub fn extract_symbols(script: &str) -> Result<Vec<Symbol>, Error> {
+    let tokens: Vec<Token> = Lexer::new(script)
+        .tokenize()?
+        .into_iter()
+        .filter(|t| !matches!(t.0, TokenType::Whitespace | TokenType::Comment))
+        .collect();
+
+    let mut symbols = Vec::new();
+    for (i, token) in tokens.iter().enumerate() {
+        if !matches!(token.0, TokenType::Identifier) && !token.could_be_identifier() {
+            continue;
+        }
+        let before = i.checked_sub(1).map(|i| &tokens[i]);
+        let (kind, span) = match before {
+            Some(dollar) if dollar.0 == TokenType::Variable && dollar.1.end == token.1.start => (
+                SymbolKind::Variable,
+                Span::merge(dollar.span(), token.span()),
+            ),
+            Some(Token(TokenType::Keyword(Keyword::Function), ..)) => {
+                (SymbolKind::Function, token.span())
+            }
+            Some(Token(
+                TokenType::Keyword(
+                    Keyword::Class | Keyword::Interface | Keyword::Trait | Keyword::Enum,
+                ),
+                ..,
+            )) => (SymbolKind::Class, token.span()),
+            _ if token.could_be_identifier() => continue,
+            _ => (SymbolKind::Identifier, token.span()),
+        };
+        symbols.push(Symbol {
+            name: token.2.clone().unwrap_or_default(),
+            kind,
+            span,
+        });
+    }
+    Ok(symbols)
+}
+
+/// Why a delimiter was reported by `find_unbalanced`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DelimiterError {
+    /// An opening delimiter that is never closed.
+    Unclosed,
+    /// A closing delimiter without an opening delimiter.
+    Unopened,
+    /// A closing delimiter of a different kind than the delimiter it closes,
+    /// eg: the `]` of `(]`.
+    Mismatched {
+        /// The span of the opening delimiter.
+        open: Span,
+    },
+}
+
+/// Finds every bracket, parenthesis, brace and attribute delimiter that is not balanced.
+/// A mismatched closing delimiter still closes the delimiter before it, so one mistake is
+/// not reported again for every delimiter that follows it.
+pub fn find_unbalanced(tokens: &[Token]) -> Vec<(Span, DelimiterError)> {
+    let mut errors = Vec::new();
+    let mut open: Vec<&Token> = Vec::new();
+    for token in tokens {
+        let opener = match token.0 {
+            TokenType::LeftParenthesis
+            | TokenType::LeftBracket
+            | TokenType::LeftBrace
+            | TokenType::AttributeStart => {
+                open.push(token);
+                continue;
+            }
+            TokenType::RightParenthesis => TokenType::LeftParenthesis,
+            TokenType::RightBracket => TokenType::LeftBracket,
+            TokenType::RightBrace => TokenType::LeftBrace,
+            TokenType::AttributeEnd => TokenType::AttributeStart,
+            _ => continue,
+        };
+
+        match open.pop() {
+            None => errors.push((token.span(), DelimiterError::Unopened)),
+            // a `]` closing a `#[` may not have been lexed as an `AttributeEnd`.
+            Some(start)
+                if start.0 == opener
+                    || (start.0 == TokenType::AttributeStart
+                        && token.0 == TokenType::RightBracket) => {}
+            Some(start) => errors.push((
+                token.span(),
+                DelimiterError::Mismatched { open: start.span() },
+            )),
+        }
+    }
+
+    errors.extend(
+        open.into_iter()
+            .map(|token| (token.span(), DelimiterError::Unclosed)),
+    );
+    errors.sort_by_key(|(span, _)| span.start);
+    errors
+}
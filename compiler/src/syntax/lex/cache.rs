@@ -0,0 +1,164 @@
+use std::{
+    collections::{hash_map::DefaultHasher, BTreeMap, HashMap},
+    hash::{Hash, Hasher},
+    io::Error,
+};
+
+#[cfg(feature = "serde")]
+use std::io::ErrorKind;
+
+#[cfg(feature = "serde")]
+use super::{analysis::LexStats, version::PhpVersion, warning::LexWarning};
+use super::{token::Token, Lexer};
+
+/// A size bounded cache of lexed scripts, keyed by a hash of their source. The source is kept
+/// to compare on a hit, so two scripts with the same hash are never confused, the script cached
+/// last replaces the other.
+/// Once full, the least recently used script is evicted.
+pub struct TokenCache {
+    capacity: usize,
+    /// The cached scripts, by the hash of their source.
+    entries: HashMap<u64, Entry>,
+    /// The hash of each cached script, by when it was last used, from least to most recently.
+    order: BTreeMap<u64, u64>,
+    /// The amount of times a script was looked up, which orders when scripts were last used.
+    uses: u64,
+    /// The amount of times a script had to be lexed.
+    lexes: usize,
+}
+
+/// A script cached by `TokenCache`.
+struct Entry {
+    script: String,
+    tokens: Vec<Token>,
+    /// When the script was last used, see `TokenCache::uses`.
+    used: u64,
+}
+
+impl TokenCache {
+    /// Creates a cache holding up to `capacity` scripts. The capacity is at least 1, as the
+    /// tokens returned by `tokenize` are kept in the cache.
+    pub fn new(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        Self {
+            capacity,
+            entries: HashMap::with_capacity(capacity),
+            order: BTreeMap::new(),
+            uses: 0,
+            lexes: 0,
+        }
+    }
+
+    /// Gets the tokens of the given script, only lexing it if it isn't cached.
+    pub fn tokenize(&mut self, script: &str) -> Result<&[Token], Error> {
+        let key = hash(script);
+        self.uses += 1;
+
+        match self.entries.get_mut(&key) {
+            Some(entry) if entry.script == script => {
+                self.order.remove(&entry.used);
+                entry.used = self.uses;
+            }
+            _ => {
+                let tokens = Lexer::new(script).tokenize()?;
+                self.lexes += 1;
+
+                if let Some(entry) = self.entries.remove(&key) {
+                    self.order.remove(&entry.used);
+                } else if self.entries.len() >= self.capacity {
+                    if let Some((_, evicted)) = self.order.pop_first() {
+                        self.entries.remove(&evicted);
+                    }
+                }
+                let entry = Entry {
+                    script: script.to_string(),
+                    tokens,
+                    used: self.uses,
+                };
+                self.entries.insert(key, entry);
+            }
+        }
+        self.order.insert(self.uses, key);
+
+        Ok(&self.entries[&key].tokens)
+    }
+
+    /// The amount of times a script had to be lexed, rather than being retrieved from the cache.
+    pub fn lexes(&self) -> usize {
+        self.lexes
+    }
+
+    /// The amount of scripts currently cached.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.order.clear();
+    }
+}
+
+/// Everything lexed from a script, which may be serialized to cache it on disk between runs.
+/// Loading a cached result with `LexResult::from_bytes` is cheaper than lexing the script again.
+#[cfg(feature = "serde")]
+#[derive(
+    Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize, bincode::Encode, bincode::Decode,
+)]
+pub struct LexResult {
+    /// The version of PHP the script was lexed against.
+    pub version: PhpVersion,
+    /// The tokens lexed before the script ended, or lexing failed.
+    pub tokens: Vec<Token>,
+    pub warnings: Vec<LexWarning>,
+    /// The statistics collected, if enabled with `Lexer::collect_stats`.
+    pub stats: Option<LexStats>,
+    /// The message of the error that stopped lexing, if any.
+    pub error: Option<String>,
+}
+
+#[cfg(feature = "serde")]
+impl LexResult {
+    /// Lexes every remaining token in the script, keeping the error that stopped lexing, if any.
+    pub fn new(mut lexer: Lexer<'_>) -> Self {
+        let mut tokens = Vec::new();
+        let error = loop {
+            match lexer.next() {
+                Ok(Some(token)) => tokens.push(token),
+                Ok(None) => break None,
+                Err(error) => break Some(error.to_string()),
+            }
+        };
+
+        Self {
+            version: lexer.target,
+            tokens,
+            warnings: lexer.warnings().to_vec(),
+            stats: lexer.stats().cloned(),
+            error,
+        }
+    }
+
+    /// Serializes the result to a compact binary format.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, Error> {
+        bincode::encode_to_vec(self, bincode::config::standard())
+            .map_err(|e| Error::new(ErrorKind::InvalidData, e))
+    }
+
+    /// Deserializes a result from the bytes given by `LexResult::to_bytes`.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        let (result, _) = bincode::decode_from_slice(bytes, bincode::config::standard())
+            .map_err(|e| Error::new(ErrorKind::InvalidData, e))?;
+        Ok(result)
+    }
+}
+
+fn hash(script: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    script.hash(&mut hasher);
+    hasher.finish()
+}
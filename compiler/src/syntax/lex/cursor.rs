@@ -1,45 +1,48 @@
-use std::{io::Error, io::ErrorKind, str::Chars};
+use std::{io::Error, str::Chars};
+
+use super::token::Location;
 
 pub const END_OF_FILE: char = '\0';
+pub const BYTE_ORDER_MARK: char = '\u{feff}';
 
 /// A struct that handles a stream of chars
+#[derive(Clone)]
 pub struct Cursor<'a> {
-    ilen: usize,
     chars: Chars<'a>,
     prev: char,
-    /// the current index in the chars buffer.
+    /// the current byte offset in the chars buffer.
     index: usize,
+    /// the current line and column.
+    location: Location,
 }
 
 impl<'a> Cursor<'a> {
     pub fn new(input: &'a str) -> Cursor<'a> {
+        Cursor::at(input, 0, Location::START)
+    }
+
+    /// Creates a cursor resuming at the byte offset `index` of the input,
+    /// which is at the given `location`.
+    pub fn at(input: &'a str, index: usize, location: Location) -> Cursor<'a> {
         Cursor {
-            ilen: input.len(),
-            chars: input.chars(),
+            chars: input[index..].chars(),
             prev: END_OF_FILE,
-            index: 0,
+            index,
+            location,
         }
     }
 
     pub fn peek(&mut self) -> Option<char> {
-        match self.chars.next() {
-            Some(c) => {
-                self.prev = c;
-
-                if self.is_eof() {
-                    return None;
-                }
-
-                self.index += 1;
-
-                Some(c)
-            }
-            None => None,
+        let c = self.chars.next()?;
+        self.prev = c;
+        self.index += c.len_utf8();
+        if c == '\n' {
+            self.location.line += 1;
+            self.location.column = 1;
+        } else {
+            self.location.column += 1;
         }
-    }
-
-    pub fn unpeek(&mut self) -> char {
-        self.chars.nth(self.eaten() - 1).unwrap_or(END_OF_FILE)
+        Some(c)
     }
 
     /// Is End of file?
@@ -60,9 +63,7 @@ impl<'a> Cursor<'a> {
     /// Returns the `nth_char` releative to the current cursor pos
     /// If the position given doesn't exist, `END_OF_FILE` is returned.
     pub fn nth_char(&self, amt: usize) -> Result<char, Error> {
-        self.chars()
-            .nth(amt)
-            .ok_or_else(|| Error::from(ErrorKind::UnexpectedEof))
+        Ok(self.chars().nth(amt).unwrap_or(END_OF_FILE))
     }
 
     /// Copies the current chars in the cursor.
@@ -70,25 +71,22 @@ impl<'a> Cursor<'a> {
         self.chars.clone()
     }
 
-    pub fn get_pos(&self) -> usize {
-        self.index.clone()
+    /// The remainder of the input that has not been consumed yet.
+    pub fn rest(&self) -> &'a str {
+        self.chars.as_str()
     }
 
-    pub fn get_prev(&self) -> char {
-        self.prev.clone()
+    pub fn get_pos(&self) -> usize {
+        self.index
     }
 
-    pub fn peek_get_pos(&mut self) -> usize {
-        self.peek();
-        self.index.clone()
+    /// The line and column of the next char.
+    pub fn location(&self) -> Location {
+        self.location
     }
 
-    /// Increment current position by `x` and return the new position.
-    ///
-    /// ! DOES NOT PEEK, FOR PEEK INCREMENTING USE `peek_inc`
-    pub fn ipeek_get_pos(&mut self, x: usize) -> usize {
-        self.peek_inc(x);
-        self.index.clone()
+    pub fn get_prev(&self) -> char {
+        self.prev
     }
 
     /// Increments the current buffer with the given one.
@@ -101,14 +99,9 @@ impl<'a> Cursor<'a> {
         }
     }
 
-    /// Shows how many chars have been consumed by the cursor.
-    pub fn eaten(&self) -> usize {
-        self.ilen - self.chars.as_str().len()
-    }
-
     pub fn eat_while(&mut self, mut pred: impl FnMut(char) -> bool) -> Result<String, Error> {
         let mut segment = String::new();
-        while !self.is_eof() && pred(self.first()?) == true {
+        while !self.is_eof() && pred(self.first()?) {
             segment.push(self.peek().unwrap_or(END_OF_FILE));
         }
         Ok(segment)
@@ -119,13 +112,9 @@ impl<'a> Cursor<'a> {
         mut pred: impl FnMut(&mut Cursor<'a>, char) -> bool,
     ) -> Result<String, Error> {
         let mut segment = String::new();
-        while !self.is_eof() && pred(self, self.first()?) == true {
+        while !self.is_eof() && pred(self, self.first()?) {
             segment.push(self.peek().unwrap_or(END_OF_FILE));
         }
         Ok(segment)
     }
 }
-
-pub fn is_line_ending(c: char) -> bool {
-    c == '\n'
-}
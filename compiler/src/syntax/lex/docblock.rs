@@ -0,0 +1,270 @@
+use std::borrow::Cow;
+
+/// A token of a doc comment, lexed with `tokenize_docblock`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DocToken {
+    /// A tag, including its `@`, eg: `@param` or `@phpstan-return`.
+    Tag(String),
+    /// A name within a type, eg: `int` or `\Foo\Bar`.
+    Type(String),
+    /// The `<` opening the arguments of a generic type, eg: `array<int>`.
+    GenericOpen,
+    /// The `>` closing the arguments of a generic type.
+    GenericClose,
+    /// The `,` separating the arguments of a generic type.
+    Comma,
+    /// The `|` of a union type, eg: `int|string`.
+    Union,
+    /// The `&` of an intersection type, eg: `A&B`.
+    Intersection,
+    /// The `?` of a nullable type, eg: `?int`.
+    Nullable,
+    /// The `[]` of an array type, eg: `int[]`.
+    Array,
+    /// Any other char within a type, eg: the braces and colons of `array{id: int}`.
+    Punctuation(char),
+    /// The variable a tag documents, including its `$` and any `&` or `...` before it,
+    /// eg: `$x` or `...$xs`.
+    Variable(String),
+    /// The text of a line, or what follows the type and variable of a tag.
+    Text(String),
+}
+
+/// Lexes the contents of a `/** */` comment. The comment markers and the `*` starting each
+/// line are stripped. A tag that takes a type, such as `@param`, may be followed by one, which
+/// ends at the first whitespace outside of its brackets, so `<` and `>` are type brackets rather
+/// than operators, eg: `@param array<int, string> $x`. Any other tag is followed by its text,
+/// eg: `@deprecated Use $list.`.
+pub fn tokenize_docblock(comment: &str) -> Vec<DocToken> {
+    let comment = comment.trim();
+    let comment = comment.strip_prefix("/**").unwrap_or(comment);
+    let comment = comment.strip_suffix("*/").unwrap_or(comment);
+
+    let mut tokens = Vec::new();
+    for line in comment.lines() {
+        let line = line.trim_start();
+        let line = line.strip_prefix('*').unwrap_or(line).trim();
+        if line.is_empty() {
+            continue;
+        }
+        if !line.starts_with('@') {
+            tokens.push(DocToken::Text(line.to_string()));
+            continue;
+        }
+
+        let tag_len = line
+            .find(|c: char| !(c.is_alphanumeric() || matches!(c, '@' | '-' | '_' | '\\')))
+            .unwrap_or(line.len());
+        tokens.push(DocToken::Tag(line[..tag_len].to_string()));
+
+        let tag = &line[..tag_len];
+        let mut rest = line[tag_len..].trim_start();
+        if !is_typed_tag(tag) {
+            if !rest.is_empty() {
+                tokens.push(DocToken::Text(rest.to_string()));
+            }
+            continue;
+        }
+
+        if rest.starts_with(is_type_start) {
+            rest = eat_type(rest, &mut tokens).trim_start();
+        }
+        let sigil = rest.trim_start_matches('&');
+        let sigil = sigil.strip_prefix("...").unwrap_or(sigil);
+        if let Some(name) = sigil.strip_prefix('$') {
+            let len = rest.len()
+                - name
+                    .trim_start_matches(|c: char| c.is_alphanumeric() || c == '_')
+                    .len();
+            tokens.push(DocToken::Variable(rest[..len].to_string()));
+            rest = rest[len..].trim_start();
+        }
+        if !rest.is_empty() {
+            tokens.push(DocToken::Text(rest.to_string()));
+        }
+    }
+    tokens
+}
+
+/// The tags followed by a type, along with their `@phpstan-` and `@psalm-` forms, eg:
+/// `@phpstan-param`.
+const TYPED_TAGS: &[&str] = &[
+    "param",
+    "return",
+    "var",
+    "throws",
+    "property",
+    "property-read",
+    "property-write",
+    "method",
+];
+
+/// Whether the tag, including its `@`, is followed by a type, see `TYPED_TAGS`.
+fn is_typed_tag(tag: &str) -> bool {
+    let name = tag.strip_prefix('@').unwrap_or(tag);
+    let name = ["phpstan-", "psalm-"]
+        .iter()
+        .find_map(|prefix| name.strip_prefix(prefix))
+        .unwrap_or(name);
+    TYPED_TAGS.contains(&name)
+}
+
+/// A doc comment, parsed with `parse_docblock`.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct DocBlock {
+    /// The first line of text, eg: `Adds two numbers.`.
+    pub summary: Option<String>,
+    /// The lines of text following the summary and preceding the tags, joined by line breaks.
+    pub description: Option<String>,
+    pub params: Vec<DocTag>,
+    pub returns: Option<DocTag>,
+    pub vars: Vec<DocTag>,
+    pub throws: Vec<DocTag>,
+}
+
+/// A `@param`, `@return`, `@var` or `@throws` tag of a `DocBlock`.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct DocTag {
+    /// The type, with any whitespace within it removed, eg: `array<int,string>`.
+    pub ty: Option<String>,
+    /// The variable, including its `$`, eg: `$x`.
+    pub name: Option<String>,
+    /// The text following the type and variable, including any lines below the tag.
+    pub description: Option<String>,
+}
+
+/// Parses the summary, description and tags of a `/** */` comment, see `tokenize_docblock`.
+/// Any other tag, such as `@deprecated`, is skipped along with its text.
+pub fn parse_docblock(comment: &str) -> DocBlock {
+    let mut block = DocBlock::default();
+    // the tag the text being read belongs to, `None` before the first tag.
+    let mut tag: Option<(String, DocTag)> = None;
+
+    for token in tokenize_docblock(comment) {
+        match token {
+            DocToken::Tag(name) => {
+                if let Some((name, tag)) = tag.take() {
+                    block.push_tag(&name, tag);
+                }
+                tag = Some((name, DocTag::default()));
+            }
+            DocToken::Variable(name) => {
+                if let Some((_, tag)) = &mut tag {
+                    tag.name = Some(name);
+                }
+            }
+            DocToken::Text(text) => match &mut tag {
+                Some((_, tag)) => append_line(&mut tag.description, &text),
+                None if block.summary.is_none() => block.summary = Some(text),
+                None => append_line(&mut block.description, &text),
+            },
+            token => {
+                if let Some((_, tag)) = &mut tag {
+                    tag.ty
+                        .get_or_insert_with(String::new)
+                        .push_str(&type_text(&token));
+                }
+            }
+        }
+    }
+    if let Some((name, tag)) = tag {
+        block.push_tag(&name, tag);
+    }
+    block
+}
+
+impl DocBlock {
+    fn push_tag(&mut self, name: &str, tag: DocTag) {
+        match name {
+            "@param" => self.params.push(tag),
+            "@return" => self.returns = Some(tag),
+            "@var" => self.vars.push(tag),
+            "@throws" => self.throws.push(tag),
+            _ => {}
+        }
+    }
+}
+
+/// Appends a line of text, separating it from the text before it with a line break.
+fn append_line(text: &mut Option<String>, line: &str) {
+    match text {
+        Some(text) => {
+            text.push('\n');
+            text.push_str(line);
+        }
+        None => *text = Some(line.to_string()),
+    }
+}
+
+/// The text of a token within a type, eg: `<` for `GenericOpen`.
+fn type_text(token: &DocToken) -> Cow<'_, str> {
+    Cow::Borrowed(match token {
+        DocToken::Type(name) => name,
+        DocToken::GenericOpen => "<",
+        DocToken::GenericClose => ">",
+        DocToken::Comma => ",",
+        DocToken::Union => "|",
+        DocToken::Intersection => "&",
+        DocToken::Nullable => "?",
+        DocToken::Array => "[]",
+        DocToken::Punctuation(c) => return Cow::Owned(c.to_string()),
+        DocToken::Tag(text) | DocToken::Variable(text) | DocToken::Text(text) => text,
+    })
+}
+
+/// Eats the type at the start of `rest` into `tokens`, returning what follows it.
+fn eat_type<'a>(rest: &'a str, tokens: &mut Vec<DocToken>) -> &'a str {
+    let mut depth = 0usize;
+    let mut chars = rest.char_indices().peekable();
+    while let Some(&(i, c)) = chars.peek() {
+        if c.is_whitespace() && depth == 0 {
+            return &rest[i..];
+        }
+        chars.next();
+
+        let token = match c {
+            c if is_name_char(c) || c == '\\' => {
+                let len = rest[i..]
+                    .find(|c: char| !(is_name_char(c) || c == '\\'))
+                    .unwrap_or(rest.len() - i);
+                while chars.next_if(|&(j, _)| j < i + len).is_some() {}
+                DocToken::Type(rest[i..i + len].to_string())
+            }
+            '<' | '{' | '(' => {
+                depth += 1;
+                match c {
+                    '<' => DocToken::GenericOpen,
+                    _ => DocToken::Punctuation(c),
+                }
+            }
+            '>' | '}' | ')' => {
+                depth = depth.saturating_sub(1);
+                match c {
+                    '>' => DocToken::GenericClose,
+                    _ => DocToken::Punctuation(c),
+                }
+            }
+            '[' if rest[i..].starts_with("[]") => {
+                chars.next();
+                DocToken::Array
+            }
+            ',' => DocToken::Comma,
+            '|' => DocToken::Union,
+            '&' => DocToken::Intersection,
+            '?' => DocToken::Nullable,
+            c if c.is_whitespace() => continue,
+            c => DocToken::Punctuation(c),
+        };
+        tokens.push(token);
+    }
+    ""
+}
+
+/// Whether the char may begin a type, eg: `int`, `\Foo`, `?int` or `(A&B)|null`.
+fn is_type_start(c: char) -> bool {
+    c.is_alphabetic() || matches!(c, '_' | '\\' | '?' | '(')
+}
+
+fn is_name_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '_' | '-')
+}
@@ -0,0 +1,142 @@
+use std::{
+    fmt,
+    io::{Error, ErrorKind},
+    ops::Range,
+};
+
+/// An error that occurs while lexing a script.
+///
+/// The lexer surfaces these wrapped in a `std::io::Error` of kind `InvalidData`,
+/// `LexError::from_io` may be used to retrieve them.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LexError {
+    /// A character that does not begin any valid token.
+    UnexpectedChar {
+        character: char,
+        span: Range<usize>,
+        /// A suggestion on how the error may be fixed.
+        hint: Option<&'static str>,
+    },
+    /// A limit set on the lexer was exceeded.
+    LimitExceeded {
+        limit: Limit,
+        /// The maximum that was set for the limit.
+        max: usize,
+        span: Range<usize>,
+    },
+    /// A byte sequence that is not valid UTF-8.
+    InvalidUtf8 { span: Range<usize> },
+    /// An escape within a double quoted string that is malformed or is not a valid code
+    /// point, eg: `"\u{D800}"`.
+    InvalidEscape { span: Range<usize> },
+    /// A numeric literal that is malformed, eg: `1__000`, `0x` or `1e`.
+    InvalidNumber { span: Range<usize> },
+    /// A sequence of operators that is never valid, most likely a typo, eg: `??->`.
+    InvalidOperator {
+        operator: &'static str,
+        span: Range<usize>,
+        /// What was most likely meant instead.
+        hint: &'static str,
+    },
+    /// A range given to `Lexer::new_range` that is out of bounds or splits a char.
+    InvalidRange { span: Range<usize> },
+}
+
+/// A limit that may be set on the `Lexer` to protect against pathological scripts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Limit {
+    /// The length of the script in bytes.
+    InputLength,
+    /// How deeply brackets, parenthesis and braces are nested.
+    NestingDepth,
+}
+
+impl LexError {
+    /// Retrieves the `LexError` wrapped by an error returned from the lexer, if any.
+    pub fn from_io(error: &Error) -> Option<&LexError> {
+        error.get_ref()?.downcast_ref()
+    }
+
+    /// The byte range of the script the error occurred at.
+    pub fn span(&self) -> Range<usize> {
+        match self {
+            LexError::UnexpectedChar { span, .. }
+            | LexError::LimitExceeded { span, .. }
+            | LexError::InvalidUtf8 { span }
+            | LexError::InvalidEscape { span }
+            | LexError::InvalidNumber { span }
+            | LexError::InvalidOperator { span, .. }
+            | LexError::InvalidRange { span } => span.clone(),
+        }
+    }
+}
+
+impl fmt::Display for LexError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LexError::UnexpectedChar {
+                character,
+                span,
+                hint,
+            } => {
+                write!(
+                    f,
+                    "Unexpected character {:?} at {} until {}",
+                    character, span.start, span.end
+                )?;
+                if let Some(hint) = hint {
+                    write!(f, ", {}", hint)?;
+                }
+                Ok(())
+            }
+            LexError::LimitExceeded { limit, max, span } => {
+                let name = match limit {
+                    Limit::InputLength => "input length",
+                    Limit::NestingDepth => "nesting depth",
+                };
+                write!(
+                    f,
+                    "Exceeded the maximum {} of {} at {} until {}",
+                    name, max, span.start, span.end
+                )
+            }
+            LexError::InvalidUtf8 { span } => write!(
+                f,
+                "Invalid UTF-8 sequence at {} until {}",
+                span.start, span.end
+            ),
+            LexError::InvalidEscape { span } => write!(
+                f,
+                "Invalid escape sequence at {} until {}",
+                span.start, span.end
+            ),
+            LexError::InvalidNumber { span } => write!(
+                f,
+                "Invalid numeric literal at {} until {}",
+                span.start, span.end
+            ),
+            LexError::InvalidOperator {
+                operator,
+                span,
+                hint,
+            } => write!(
+                f,
+                "Invalid operator {:?} at {} until {}, {}",
+                operator, span.start, span.end, hint
+            ),
+            LexError::InvalidRange { span } => write!(
+                f,
+                "Invalid range {} until {}, it must be within the script and on char boundaries",
+                span.start, span.end
+            ),
+        }
+    }
+}
+
+impl std::error::Error for LexError {}
+
+impl From<LexError> for Error {
+    fn from(error: LexError) -> Self {
+        Error::new(ErrorKind::InvalidData, error)
+    }
+}
@@ -0,0 +1,270 @@
+use std::{io::Error, ops::Range};
+
+use crate::syntax::ast::operator::Arity;
+
+use super::{
+    token::{StringType, Token, TokenType},
+    CommentPolicy, HtmlMode, Lexer,
+};
+
+/// Prints one token per line as its index, kind, byte range and text, aligned in columns.
+///
+/// ```text
+/// 0  Variable    0..1  "$"
+/// 1  Identifier  1..2  "a"
+/// 2  EOS         2..3  ";"
+/// ```
+pub fn debug_tokens(tokens: &[Token]) -> String {
+    let rows: Vec<[String; 4]> = tokens
+        .iter()
+        .map(|token| {
+            [
+                token.index().to_string(),
+                format!("{:?}", token.0),
+                format!("{:?}", token.1),
+                token
+                    .text()
+                    .map(|text| format!("{:?}", text))
+                    .unwrap_or_default(),
+            ]
+        })
+        .collect();
+
+    let mut widths = [0; 4];
+    for row in &rows {
+        for (width, column) in widths.iter_mut().zip(row) {
+            *width = (*width).max(column.chars().count());
+        }
+    }
+
+    let mut output = String::new();
+    for row in &rows {
+        let mut line = String::new();
+        for (column, width) in row.iter().zip(widths) {
+            line.push_str(&format!("{:<width$}  ", column, width = width));
+        }
+        output.push_str(line.trim_end());
+        output.push('\n');
+    }
+    output
+}
+
+/// Re-emits the tokens as source, with one space around binary operators and none after
+/// unary operators, eg: `1+2` becomes `1 + 2` and `- $x` becomes `-$x`. Any other whitespace
+/// is kept, as are line breaks around operators.
+///
+/// An operator is unary when the token before it does not end an operand, eg: the `-` in
+/// `$a = -$b`. Every other token is written as it was in the script the tokens were lexed
+/// from, so strings keep their escapes, eg: `"\x41"`.
+pub fn normalize_spacing(tokens: &[Token], script: &str) -> String {
+    let mut output = String::new();
+    // whether horizontal whitespace after the previous token is left out.
+    let mut skip_space = false;
+    let mut operand = false;
+
+    for (i, token) in tokens.iter().enumerate() {
+        match &token.0 {
+            TokenType::Whitespace => {
+                let space = token.2.as_deref().unwrap_or_default();
+                if skip_space && !space.contains('\n') {
+                    continue;
+                }
+                if skip_space {
+                    output.truncate(output.trim_end_matches(' ').len());
+                }
+                output.push_str(space);
+                skip_space = false;
+                continue;
+            }
+            TokenType::LF(_) | TokenType::Comment => {
+                if skip_space {
+                    output.truncate(output.trim_end_matches(' ').len());
+                }
+                output.push_str(token.source(script));
+                skip_space = false;
+                continue;
+            }
+            TokenType::Operator | TokenType::Dot if !is_increment(tokens, i) => {
+                let unary = match token.operator_kind().map(|kind| kind.arity()) {
+                    Some(Arity::Unary) => true,
+                    Some(Arity::Both) => !operand,
+                    _ => false,
+                };
+                if !unary {
+                    let trimmed = output.trim_end_matches([' ', '\t']);
+                    if !trimmed.is_empty() && !trimmed.ends_with('\n') {
+                        output.truncate(trimmed.len());
+                        output.push(' ');
+                    }
+                }
+                output.push_str(token.source(script));
+                if !unary {
+                    output.push(' ');
+                }
+                skip_space = true;
+                operand = false;
+                continue;
+            }
+            _ => {}
+        }
+
+        output.push_str(token.source(script));
+        skip_space = false;
+        operand = token.ends_operand() || (is_increment(tokens, i) && operand);
+    }
+    output
+}
+
+/// Whether the token is one of the `+` or `-` pair an increment or decrement is lexed as,
+/// eg: `$i++`, which is kept as written.
+fn is_increment(tokens: &[Token], index: usize) -> bool {
+    let token = &tokens[index];
+    let pairs = |other: &Token| other.0 == TokenType::Operator && other.2 == token.2;
+
+    token.0 == TokenType::Operator
+        && matches!(token.2.as_deref(), Some("+" | "-"))
+        && (index
+            .checked_sub(1)
+            .is_some_and(|i| pairs(&tokens[i]) && tokens[i].1.end == token.1.start)
+            || tokens
+                .get(index + 1)
+                .is_some_and(|after| pairs(after) && after.1.start == token.1.end))
+}
+
+/// Strips the comments and whitespace of a script, keeping a space only where leaving it out
+/// would merge two tokens, eg: `echo 1` must not become `echo1`, while tokens that were not
+/// separated are kept that way, eg: `$i++`. Every token is written as it
+/// was in the script, so strings and heredocs are kept verbatim.
+/// As PHP outputs text outside of its tags, a script starts outside them and that text is kept
+/// verbatim too, eg: the HTML of a template.
+pub fn minify(script: &str) -> Result<String, Error> {
+    let tokens = Lexer::new(script)
+        .html_mode(HtmlMode::Opaque)
+        .comments(CommentPolicy::DropAll)
+        .tokenize()?;
+
+    let mut output = String::new();
+    let mut before: Option<&Token> = None;
+    for token in &tokens {
+        if matches!(token.0, TokenType::Whitespace | TokenType::LF(_)) {
+            continue;
+        }
+
+        let source = token.source(script);
+        match before {
+            // an open tag other than `<?=` must be followed by whitespace.
+            Some(tag @ Token(TokenType::OpenTag, ..)) if tag.source(script) != "<?=" => {
+                output.push(' ')
+            }
+            // a close tag never merges with the token before it.
+            _ if token.0 == TokenType::CloseTag => {}
+            Some(before)
+                if before.1.end != token.1.start && merges(before.source(script), source) =>
+            {
+                output.push(' ')
+            }
+            _ => {}
+        }
+        output.push_str(source);
+        before = Some(token);
+    }
+    Ok(output)
+}
+
+/// How `code_skeleton` masks the contents of strings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StringMask {
+    /// Every byte of the contents is masked, keeping line breaks, so the skeleton has the same
+    /// length and lines as the script, eg: the span of a match within it points into the script.
+    #[default]
+    KeepLength,
+    /// The contents are masked by a single placeholder, eg: `'secret'` becomes `'x'`.
+    Collapse,
+}
+
+/// The char masking the contents of a string in `code_skeleton`.
+const MASK: char = 'x';
+
+/// The script without the contents of its comments and strings, so a scanner searching the code
+/// does not match text within them. Comments are removed, while strings keep their quotes or
+/// heredoc labels with their contents masked according to `mask`, eg: `eval('eval')` becomes
+/// `eval('xxxx')`. With `StringMask::KeepLength` comments are replaced by spaces instead.
+pub fn code_skeleton(script: &str, mask: StringMask) -> Result<String, Error> {
+    let tokens = Lexer::new(script).tokenize()?;
+
+    let mut output = String::with_capacity(script.len());
+    for (i, token) in tokens.iter().enumerate() {
+        let source = token.source(script);
+        match (&token.0, mask) {
+            (TokenType::Comment, StringMask::KeepLength) => {
+                output.extend(source.bytes().map(|b| if b == b'\n' { '\n' } else { ' ' }))
+            }
+            (TokenType::Comment, StringMask::Collapse) => {
+                // keeps the tokens around the comment apart, eg: `$a/**/$b`.
+                let spaced = output.ends_with(char::is_whitespace)
+                    || tokens.get(i + 1).is_none_or(|next| {
+                        matches!(next.0, TokenType::Whitespace | TokenType::LF(_))
+                    });
+                if !spaced {
+                    output.push(' ');
+                }
+            }
+            (TokenType::StringLit(kind), _) => {
+                let body = string_body(source, kind);
+                output.push_str(&source[..body.start]);
+                match mask {
+                    StringMask::KeepLength => {
+                        output.extend(source[body.clone()].bytes().map(|b| {
+                            if b == b'\n' {
+                                '\n'
+                            } else {
+                                MASK
+                            }
+                        }))
+                    }
+                    StringMask::Collapse if !body.is_empty() => output.push(MASK),
+                    StringMask::Collapse => {}
+                }
+                output.push_str(&source[body.end..]);
+            }
+            _ => output.push_str(source),
+        }
+    }
+    Ok(output)
+}
+
+/// The byte range of the contents of a string literal within its source, without its quotes,
+/// or the heredoc label lines.
+fn string_body(source: &str, kind: &StringType) -> Range<usize> {
+    match kind {
+        StringType::HereDoc | StringType::NowDoc => {
+            let start = source.find('\n').map_or(source.len(), |i| i + 1);
+            let end = source.rfind('\n').unwrap_or(source.len());
+            // a `\r\n` line break ends the contents at its `\r`.
+            let end = end - usize::from(source[..end].ends_with('\r'));
+            start.min(end)..end
+        }
+        StringType::Single | StringType::Double => {
+            let start = source.find(['\'', '"']).map_or(0, |i| i + 1);
+            start..source.len().saturating_sub(1).max(start)
+        }
+    }
+}
+
+/// Whether the two tokens lex differently when they are not separated by whitespace.
+/// This is checked without the tokens before them, so a space may be kept where the context
+/// would have kept them apart, eg: the `.` and `5` of `$a . 5`. Two `+` or `-` always merge,
+/// as PHP would lex them as an increment or decrement.
+fn merges(before: &str, after: &str) -> bool {
+    if (before.ends_with('+') && after.starts_with('+'))
+        || (before.ends_with('-') && after.starts_with('-'))
+    {
+        return true;
+    }
+
+    let joined = format!("{}{}", before, after);
+    match Lexer::new(&joined).tokenize() {
+        Ok(tokens) => tokens.len() != 2 || tokens[0].1.end != before.len(),
+        Err(_) => true,
+    }
+}
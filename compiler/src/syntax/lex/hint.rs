@@ -0,0 +1,363 @@
+use crate::syntax::ast::{keyword::Keyword, operator::OperatorKind};
+
+use super::token::{Token, TokenType};
+
+/// The role a `:` plays within a script, guessed from the tokens surrounding it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColonRole {
+    /// The `:` separating the branches of a ternary, eg: `$a ? $b : $c` or `$a ?: $b`.
+    Ternary,
+    /// The `:` following the name of a named argument, eg: `foo(width: 10)`.
+    NamedArgument,
+    /// The `:` introducing the return type of a function, eg: `function foo(): static`.
+    ReturnType,
+    /// The `:` defining a label, a target of `goto`, eg: `end:` for `goto end;`.
+    /// See `is_label` for how a label is told apart from a `case X:`.
+    Label,
+    /// Any other use of `:`.
+    Other,
+}
+
+/// Guesses the role of the colon at `index` within `tokens`.
+/// Returns `None` if the token at `index` is not a colon.
+pub fn colon_role(tokens: &[Token], index: usize) -> Option<ColonRole> {
+    if !matches!(tokens.get(index)?.0, TokenType::Colon) {
+        return None;
+    }
+
+    let mut before = significant_before(tokens, index);
+    let name = before.next();
+    let separator = before.next();
+
+    if matches!(
+        name.map(|t| &t.0),
+        Some(TokenType::Identifier | TokenType::Keyword(_))
+    ) && matches!(
+        separator.map(|t| &t.0),
+        Some(TokenType::LeftParenthesis | TokenType::Comma)
+    ) && matches!(
+        enclosing_open(tokens, index).map(|t| &t.0),
+        Some(TokenType::LeftParenthesis)
+    ) {
+        return Some(ColonRole::NamedArgument);
+    }
+
+    if is_return_type(tokens, index) {
+        return Some(ColonRole::ReturnType);
+    }
+
+    if is_label(tokens, index) {
+        return Some(ColonRole::Label);
+    }
+
+    if in_ternary(tokens, index) {
+        return Some(ColonRole::Ternary);
+    }
+
+    Some(ColonRole::Other)
+}
+
+/// Whether the colon at `index` defines a label: it must follow an identifier at the start of
+/// a statement, that is after a `;`, `{`, `}`, open tag or the `:` of another label or case.
+/// This rules out ternaries and named arguments, as their names follow an operator, `(` or `,`,
+/// and a `case X:` or `default:`, as their names follow the keyword. As labels may be defined
+/// after the `goto` jumping to them, whether the label is ever used is left to the parser.
+fn is_label(tokens: &[Token], index: usize) -> bool {
+    let mut before = significant_before(tokens, index);
+    if !matches!(before.next(), Some(Token(TokenType::Identifier, ..))) {
+        return false;
+    }
+    match before.next().map(|t| &t.0) {
+        None
+        | Some(
+            TokenType::EOS | TokenType::LeftBrace | TokenType::RightBrace | TokenType::OpenTag,
+        ) => true,
+        Some(TokenType::Colon) => {
+            let colon = tokens[..index - 1]
+                .iter()
+                .rposition(|t| t.0 == TokenType::Colon)
+                .unwrap_or_default();
+            !in_ternary(tokens, colon)
+        }
+        _ => false,
+    }
+}
+
+/// Whether the colon at `index` follows the parameter list of a function, closure or arrow
+/// function, or the `use` list of a closure.
+fn is_return_type(tokens: &[Token], index: usize) -> bool {
+    let Some(close) = tokens[..index].iter().rposition(|t| !is_trivia(t)) else {
+        return false;
+    };
+    if !matches!(tokens[close].0, TokenType::RightParenthesis) {
+        return false;
+    }
+    let Some(open) = enclosing_open_index(tokens, close) else {
+        return false;
+    };
+
+    let mut before = significant_before(tokens, open).map(|t| &t.0);
+    match before.next() {
+        Some(TokenType::Keyword(Keyword::Function | Keyword::Fn | Keyword::Use)) => true,
+        Some(TokenType::Identifier | TokenType::Keyword(_)) => matches!(
+            before.find(|t| !matches!(t, TokenType::Operator)),
+            Some(TokenType::Keyword(Keyword::Function))
+        ),
+        _ => false,
+    }
+}
+
+/// Whether the `|` or `&` at `index` joins the types of a union or intersection type, eg:
+/// `int|string $x`, `A&B $y` or the return type of `function f(): A|null`, rather than being
+/// a bitwise operator. Both are lexed as an `Operator` either way.
+///
+/// The operator must be between two type names, and the types must either be followed by the
+/// variable they declare or follow the `:` of a return type. A `&` directly before a variable
+/// passes it by reference, eg: `A &$y`, so it does not join types.
+pub fn is_type_operator(tokens: &[Token], index: usize) -> bool {
+    if !matches!(
+        tokens.get(index).and_then(|t| t.operator_kind()),
+        Some(OperatorKind::BitOr | OperatorKind::BitAnd)
+    ) {
+        return false;
+    }
+
+    let before = significant_before(tokens, index).next();
+    let after = tokens[index + 1..].iter().find(|t| !is_trivia(t));
+    let is_name = |token: Option<&Token>| {
+        matches!(
+            token.map(|t| &t.0),
+            Some(
+                TokenType::Identifier
+                    | TokenType::Null
+                    | TokenType::Boolean
+                    | TokenType::Keyword(Keyword::Static)
+            )
+        )
+    };
+    if !(is_name(before) || matches!(before.map(|t| &t.0), Some(TokenType::RightParenthesis)))
+        || !(is_name(after)
+            || matches!(
+                after.map(|t| &t.0),
+                Some(TokenType::Backslash | TokenType::QuestionMark | TokenType::LeftParenthesis)
+            ))
+    {
+        return false;
+    }
+
+    let declares_variable = matches!(
+        tokens[index + 1..]
+            .iter()
+            .find(|t| !is_trivia(t) && !is_type_part(t))
+            .map(|t| &t.0),
+        Some(TokenType::Variable)
+    );
+    let returned = tokens[..index]
+        .iter()
+        .rposition(|t| !is_trivia(t) && !is_type_part(t))
+        .is_some_and(|i| colon_role(tokens, i) == Some(ColonRole::ReturnType));
+    declares_variable || returned
+}
+
+/// Whether the `?` at `index` makes the type following it nullable, eg: `?int $x` or the return
+/// type of `function f(): ?string`, rather than being part of a ternary. The `?` is lexed as a
+/// `QuestionMark` either way, separately from the type name.
+///
+/// The type must either declare a parameter or property, following a `(`, `,`, attribute or
+/// modifier and followed by its variable, or follow the `:` of a return type.
+pub fn is_nullable_type(tokens: &[Token], index: usize) -> bool {
+    if !matches!(
+        tokens.get(index).map(|t| &t.0),
+        Some(TokenType::QuestionMark)
+    ) {
+        return false;
+    }
+    let after = tokens[index + 1..].iter().find(|t| !is_trivia(t));
+    if !matches!(
+        after.map(|t| &t.0),
+        Some(TokenType::Identifier | TokenType::Backslash | TokenType::Keyword(Keyword::Static))
+    ) {
+        return false;
+    }
+
+    let Some(before) = tokens[..index].iter().rposition(|t| !is_trivia(t)) else {
+        return false;
+    };
+    match &tokens[before].0 {
+        TokenType::Colon => colon_role(tokens, before) == Some(ColonRole::ReturnType),
+        TokenType::LeftParenthesis
+        | TokenType::Comma
+        | TokenType::AttributeEnd
+        | TokenType::Keyword(
+            Keyword::Public
+            | Keyword::Protected
+            | Keyword::Private
+            | Keyword::ReadOnly
+            | Keyword::Static
+            | Keyword::Var,
+        ) => matches!(
+            tokens[index + 1..]
+                .iter()
+                .find(|t| !is_trivia(t) && !is_type_part(t))
+                .map(|t| &t.0),
+            Some(TokenType::Variable)
+        ),
+        _ => false,
+    }
+}
+
+/// Whether the token may be part of a type declaration, eg: `?`, `\`, `(` or a type name.
+fn is_type_part(token: &Token) -> bool {
+    match &token.0 {
+        TokenType::Identifier
+        | TokenType::Null
+        | TokenType::Boolean
+        | TokenType::Backslash
+        | TokenType::QuestionMark
+        | TokenType::LeftParenthesis
+        | TokenType::RightParenthesis
+        | TokenType::Keyword(Keyword::Static) => true,
+        TokenType::Operator => matches!(
+            token.operator_kind(),
+            Some(OperatorKind::BitOr | OperatorKind::BitAnd)
+        ),
+        _ => false,
+    }
+}
+
+/// A type name with a special meaning within a type declaration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpecialType {
+    /// `self`, the class the declaration is written in.
+    SelfType,
+    /// `parent`, the class the declaring class extends.
+    Parent,
+    /// `static`, the class the method was called on, only allowed as a return type.
+    Static,
+    /// `never`, a function that never returns, since PHP 8.1.
+    Never,
+}
+
+/// The special type name the token spells, matched case insensitively.
+/// Only `static` is a keyword, the others lex as identifiers as they are valid names elsewhere,
+/// eg: `$this->self` or `function never()`. Returns `None` for any other token.
+pub fn special_type(token: &Token) -> Option<SpecialType> {
+    match &token.0 {
+        TokenType::Keyword(Keyword::Static) => Some(SpecialType::Static),
+        TokenType::Identifier => match token.2.as_deref()?.to_ascii_lowercase().as_str() {
+            "self" => Some(SpecialType::SelfType),
+            "parent" => Some(SpecialType::Parent),
+            "never" => Some(SpecialType::Never),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Whether the `?` at `index` begins a short ternary, eg: `$a ?: $b`.
+/// There is no single `?:` token, as PHP allows whitespace and comments between the `?` and
+/// the `:`, eg: `$a ? : $b`, so the next significant token after the `?` must be a `:`.
+pub fn is_short_ternary(tokens: &[Token], index: usize) -> bool {
+    matches!(
+        tokens.get(index).map(|t| &t.0),
+        Some(TokenType::QuestionMark)
+    ) && matches!(
+        tokens[index + 1..]
+            .iter()
+            .find(|t| !is_trivia(t))
+            .map(|t| &t.0),
+        Some(TokenType::Colon)
+    )
+}
+
+/// Whether the token is whitespace, a line break or a comment.
+fn is_trivia(token: &Token) -> bool {
+    matches!(
+        token.0,
+        TokenType::Whitespace | TokenType::LF(_) | TokenType::Comment
+    )
+}
+
+/// The tokens before `index` that are not whitespace or comments, nearest first.
+fn significant_before(tokens: &[Token], index: usize) -> impl Iterator<Item = &Token> {
+    tokens[..index].iter().rev().filter(|t| !is_trivia(t))
+}
+
+/// The unclosed bracket, parenthesis or brace containing the token at `index`.
+fn enclosing_open(tokens: &[Token], index: usize) -> Option<&Token> {
+    enclosing_open_index(tokens, index).map(|i| &tokens[i])
+}
+
+/// The index of the unclosed bracket, parenthesis or brace containing the token at `index`.
+fn enclosing_open_index(tokens: &[Token], index: usize) -> Option<usize> {
+    let mut depth = 0;
+    for (i, token) in tokens[..index].iter().enumerate().rev() {
+        match token.0 {
+            TokenType::RightParenthesis
+            | TokenType::RightBracket
+            | TokenType::RightBrace
+            | TokenType::AttributeEnd => depth += 1,
+            TokenType::LeftParenthesis
+            | TokenType::LeftBracket
+            | TokenType::LeftBrace
+            | TokenType::AttributeStart => {
+                if depth == 0 {
+                    return Some(i);
+                }
+                depth -= 1;
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Whether an unmatched `?` precedes the token at `index` within the same expression.
+fn in_ternary(tokens: &[Token], index: usize) -> bool {
+    let mut depth = 0;
+    let mut colons = 0;
+    for token in tokens[..index].iter().rev() {
+        match token.0 {
+            TokenType::RightParenthesis
+            | TokenType::RightBracket
+            | TokenType::RightBrace
+            | TokenType::AttributeEnd => depth += 1,
+            TokenType::LeftParenthesis
+            | TokenType::LeftBracket
+            | TokenType::LeftBrace
+            | TokenType::AttributeStart => {
+                if depth == 0 {
+                    return false;
+                }
+                depth -= 1;
+            }
+            TokenType::EOS if depth == 0 => return false,
+            TokenType::Colon if depth == 0 => colons += 1,
+            TokenType::QuestionMark if depth == 0 => {
+                if colons == 0 {
+                    return true;
+                }
+                colons -= 1;
+            }
+            _ => {}
+        }
+    }
+    false
+}
+
+/// How tightly the operator token binds its operands, see `OperatorKind::precedence`.
+/// This also ranks the `.` concatenation and the keyword operators, such as `new`, `clone`
+/// and `instanceof`. Returns `None` for any token that is not an operator.
+pub fn operator_precedence(token: &Token) -> Option<u8> {
+    match &token.0 {
+        TokenType::Operator => token.operator_kind()?.precedence(),
+        TokenType::Dot => Some(14),
+        TokenType::Keyword(Keyword::New | Keyword::Clone) => Some(22),
+        TokenType::Keyword(Keyword::InstanceOf) => Some(19),
+        TokenType::Keyword(Keyword::Yield | Keyword::Print) => Some(3),
+        TokenType::Keyword(Keyword::And) => Some(2),
+        TokenType::Keyword(Keyword::Xor) => Some(1),
+        TokenType::Keyword(Keyword::Or) => Some(0),
+        _ => None,
+    }
+}
@@ -0,0 +1,195 @@
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap, HashSet},
+    hash::{Hash, Hasher},
+    io::Error,
+    mem,
+    ops::Range,
+};
+
+use super::{
+    token::{Location, Token, TokenType},
+    Lexer,
+};
+
+/// The amount of tokens surrounding an edit that are always lexed again.
+/// An edit can merge or split the tokens next to it, eg: removing the space in `$a = 1`.
+const MARGIN: usize = 2;
+
+/// The result of re-lexing a script after an edit.
+#[derive(Debug, Clone)]
+pub struct Relexed {
+    pub tokens: Vec<Token>,
+    /// The amount of tokens that were lexed again, rather than reused.
+    pub relexed: usize,
+    /// The range of the previous tokens that the tokens lexed again replaced, so the tokens
+    /// before and after it were reused.
+    pub replaced: Range<usize>,
+}
+
+impl Relexed {
+    /// The identity of each token, keeping the identities `ids` of the previous `tokens` for
+    /// each token that was reused, see `token_ids`. A token lexed again keeps its identity too
+    /// when it is identical to the previous token it replaced, counting from either end of the
+    /// replaced tokens, so only the tokens the edit changed or inserted get new identities.
+    pub fn ids(&self, tokens: &[Token], ids: &[TokenId]) -> Vec<TokenId> {
+        let start = self.replaced.start;
+        let old = &tokens[self.replaced.clone()];
+        let new = &self.tokens[start..start + self.relexed];
+        let same = |a: &Token, b: &Token| a.0 == b.0 && a.2 == b.2;
+
+        let leading = old.iter().zip(new).take_while(|(a, b)| same(a, b)).count();
+        let trailing = old[leading..]
+            .iter()
+            .rev()
+            .zip(new[leading..].iter().rev())
+            .take_while(|(a, b)| same(a, b))
+            .count();
+
+        let mut result = ids[..start + leading].to_vec();
+        let kept: HashSet<TokenId> = ids[..start + leading]
+            .iter()
+            .chain(&ids[self.replaced.end - trailing..])
+            .copied()
+            .collect();
+        let mut minted = HashSet::new();
+        for token in &new[leading..new.len() - trailing] {
+            // the same hash as `token_ids`, with a count that avoids every identity in use.
+            let content = content(token);
+            let id = (1..)
+                .map(|n| {
+                    let mut hasher = DefaultHasher::new();
+                    (content, n).hash(&mut hasher);
+                    TokenId(hasher.finish())
+                })
+                .find(|id| !kept.contains(id) && !minted.contains(id))
+                .unwrap();
+            minted.insert(id);
+            result.push(id);
+        }
+        result.extend_from_slice(&ids[self.replaced.end - trailing..]);
+        result
+    }
+}
+
+/// Updates `tokens` after the byte range `edit` of the previous script was replaced with `text`.
+/// `lexer` lexes the script after the edit was applied, and must be configured as the one that
+/// lexed `tokens`, eg: with the same `HtmlMode` and target.
+///
+/// Only the tokens around the edit are lexed again. Tokens before the edit are reused as is,
+/// while tokens after the edit are reused with their ranges shifted by the change in length.
+/// The lexer resumes within the context the tokens before the edit leave it in, so an edit
+/// within an attribute group or property hooks lexes as it would in the whole script.
+pub fn relex(
+    mut lexer: Lexer<'_>,
+    tokens: &[Token],
+    edit: Range<usize>,
+    text: &str,
+) -> Result<Relexed, Error> {
+    let shift = |offset: usize| (offset + text.len()).saturating_sub(edit.len());
+
+    // the last token starting before the edit, so a token the edit touches is lexed again.
+    // Without one, eg: when the edit is within a comment that is not emitted before the first
+    // token, the whole script is lexed again.
+    let first = tokens
+        .iter()
+        .rposition(|t| t.1.start < edit.start)
+        .map_or(0, |i| i.saturating_sub(MARGIN));
+    if first > 0 {
+        let token = &tokens[first];
+        lexer.resume(&tokens[..first], token.1.start, token.4.start)?;
+    }
+
+    // the first token after the edit that may be reused.
+    let resume = tokens
+        .iter()
+        .position(|t| t.1.start > edit.end)
+        .map_or(tokens.len(), |i| (i + MARGIN).min(tokens.len()));
+
+    let mut result = tokens[..first].to_vec();
+    let mut relexed = 0;
+    let mut old = resume;
+    let mut reused = tokens.len();
+
+    while let Some(token) = lexer.next()? {
+        let end = token.1.end;
+        let end_location = token.4.end;
+        result.push(token);
+        relexed += 1;
+
+        // skip any old tokens that were lexed over.
+        while old < tokens.len() && shift(tokens[old].1.start) < end {
+            old += 1;
+        }
+
+        if old < tokens.len() && shift(tokens[old].1.start) == end {
+            let from = tokens[old].4.start;
+            let moved = |location: Location| moved(location, from, end_location);
+            result.extend(tokens[old..].iter().map(|t| {
+                let mut t = t.clone();
+                t.1 = shift(t.1.start)..shift(t.1.end);
+                t.4 = moved(t.4.start)..moved(t.4.end);
+                t
+            }));
+            reused = old;
+            break;
+        }
+    }
+
+    for (i, token) in result.iter_mut().enumerate() {
+        token.3 = i;
+    }
+
+    Ok(Relexed {
+        tokens: result,
+        relexed,
+        replaced: first..reused,
+    })
+}
+
+/// An identity of a token that is kept across edits, see `token_ids`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TokenId(u64);
+
+/// The identity of each token of a script lexed from scratch, for an editor to tell which tokens
+/// an edit changed, eg: by diffing them with the identities given by `Relexed::ids` after
+/// `relex`, which carries the identities of unchanged tokens across the edit.
+///
+/// An identity is a hash of the kind and text of the token and how many identical tokens come
+/// before it, so identities are unique, and the same tokens lexed at other offsets have the same
+/// identities. Identities are only comparable when computed by the same build of the compiler.
+pub fn token_ids(tokens: &[Token]) -> Vec<TokenId> {
+    let mut occurrences: HashMap<u64, usize> = HashMap::new();
+    tokens
+        .iter()
+        .map(|token| {
+            let content = content(token);
+            let occurrence = occurrences.entry(content).or_default();
+            *occurrence += 1;
+            let mut hasher = DefaultHasher::new();
+            (content, *occurrence).hash(&mut hasher);
+            TokenId(hasher.finish())
+        })
+        .collect()
+}
+
+/// A hash of the kind and text of the token.
+fn content(token: &Token) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    token.0.kind().hash(&mut hasher);
+    if let TokenType::StringLit(kind) = &token.0 {
+        mem::discriminant(kind).hash(&mut hasher);
+    }
+    token.text().hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Moves an unchanged location after an edit, given that `from` has moved to `to`.
+/// Only locations on the same line as `from` have their column moved.
+fn moved(location: Location, from: Location, to: Location) -> Location {
+    let line = location.line + to.line - from.line;
+    if location.line == from.line {
+        Location::new(line, location.column + to.column - from.column)
+    } else {
+        Location::new(line, location.column)
+    }
+}
@@ -0,0 +1,123 @@
+use std::io::Error;
+
+use super::{
+    cursor::Cursor,
+    is_identifier_char,
+    token::{StringType, Token, TokenType},
+    Lexer,
+};
+
+/// A piece of a double quoted string, split at its interpolations.
+#[derive(Debug, Clone, PartialEq)]
+pub enum StringPart {
+    /// Text outside of any interpolation, with its escapes decoded.
+    Literal(String),
+    /// The tokens of an interpolated expression, eg: `$name` or the `$a[$b]` within `{$a[$b]}`.
+    /// Their ranges are relative to the whole script.
+    Expression(Vec<Token>),
+}
+
+/// Splits the double quoted string `token` of `script` into its literal text and interpolated
+/// expressions. Both the `"$name"` and `"{$expression}"` forms are split, the expression of
+/// the latter ends at the `}` balancing its `{`, ignoring any braces within nested strings.
+/// A `{` that is not directly followed by a `$` is literal, eg: `"{name}"`.
+///
+/// Any other token is a single literal part of its value.
+pub fn string_parts(token: &Token, script: &str) -> Result<Vec<StringPart>, Error> {
+    if !matches!(token.0, TokenType::StringLit(StringType::Double)) {
+        return Ok(token
+            .2
+            .clone()
+            .map(StringPart::Literal)
+            .into_iter()
+            .collect());
+    }
+
+    let source = &script[token.1.clone()];
+    let start = token.1.start + 1;
+    let end = match source.len() > 1 && source.ends_with('"') {
+        true => token.1.end - 1,
+        false => token.1.end,
+    };
+
+    let mut parts = Vec::new();
+    let mut literal = start;
+    let mut i = start;
+    while i < end {
+        let rest = &script[i..end];
+        let expression = if let Some(len) = braced_len(rest) {
+            Some((i + 1..i + len - 1, len))
+        } else {
+            simple_len(rest).map(|len| (i..i + len, len))
+        };
+
+        let Some((range, len)) = expression else {
+            let mut chars = rest.chars();
+            let c = chars.next().unwrap_or_default();
+            i += c.len_utf8();
+            if c == '\\' {
+                i += chars.next().map_or(0, char::len_utf8);
+            }
+            continue;
+        };
+
+        if literal < i {
+            parts.push(StringPart::Literal(decode(&script[literal..i])?));
+        }
+        let tokens = Lexer::new_range(script, range.start, range.end)?.tokenize()?;
+        parts.push(StringPart::Expression(tokens));
+        i += len;
+        literal = i;
+    }
+
+    if literal < end {
+        parts.push(StringPart::Literal(decode(&script[literal..end])?));
+    }
+    Ok(parts)
+}
+
+/// Decodes the escapes of literal text within a double quoted string.
+fn decode(text: &str) -> Result<String, Error> {
+    Cursor::new(text).eat_double_quoted()
+}
+
+/// The length of the `{$...}` interpolation at the start of `rest`, including its braces.
+/// Braces are balanced, skipping over any quoted strings within the expression.
+/// Returns `None` if `rest` does not start with `{$`, or the interpolation is never closed.
+pub(super) fn braced_len(rest: &str) -> Option<usize> {
+    if !rest.starts_with("{$") {
+        return None;
+    }
+
+    let mut depth = 0;
+    let mut quote = None;
+    let mut chars = rest.char_indices();
+    while let Some((i, c)) = chars.next() {
+        match (quote, c) {
+            (Some(_), '\\') => {
+                chars.next();
+            }
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '\'' | '"') => quote = Some(c),
+            (None, '{') => depth += 1,
+            (None, '}') => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i + 1);
+                }
+            }
+            (None, _) => {}
+        }
+    }
+    None
+}
+
+/// The length of the `$name` interpolation at the start of `rest`.
+fn simple_len(rest: &str) -> Option<usize> {
+    let name = rest.strip_prefix('$')?;
+    if !name.starts_with(|c: char| c == '_' || c.is_alphabetic()) {
+        return None;
+    }
+    Some(1 + name.len() - name.trim_start_matches(is_identifier_char).len())
+}
@@ -1,30 +1,186 @@
 use std::{
-    io::{Error, ErrorKind},
-    str::FromStr,
+    borrow::Cow,
+    fs,
+    io::Error,
+    ops::{ControlFlow, Range},
+    path::Path,
 };
 
 use self::{
-    cursor::{Cursor, END_OF_FILE},
-    token::{AccessType, Numeric, StringType, Token, TokenType},
+    analysis::LexStats,
+    cursor::{Cursor, BYTE_ORDER_MARK, END_OF_FILE},
+    error::{LexError, Limit},
+    span::Span,
+    token::{AccessType, Location, Numeric, StringType, Token, TokenType, LF},
+    version::PhpVersion,
+    warning::{LexWarning, WarningCode},
 };
 
 use crate::token;
 
-use super::ast::keyword::{Keyword, MAX_KEYWORD_LENGTH};
+use super::ast::keyword::{Keyword, KeywordSet};
 
+pub mod analysis;
+pub mod cache;
 pub(crate) mod cursor;
+pub mod docblock;
+pub mod error;
+pub mod format;
+pub mod hint;
+pub mod incremental;
+pub mod interpolation;
+pub mod span;
+#[cfg(feature = "async")]
+pub mod stream;
+#[cfg(feature = "test-utils")]
+pub mod test_utils;
 pub mod token;
+pub mod version;
+pub mod warning;
 
-/// A trait that may be used to implement future implementations of PHP.
-pub(crate) trait Tokenizer<'a> {
-    fn lex(&mut self, cursor: &'a mut Cursor) -> Result<Token, Error>;
+/// Operators that span multiple characters.
+/// These are matched in order, so longer operators must come before any operator they start with.
+const OPERATORS: &[&str] = &[
+    "<=>", "===", "!==", "??=", "**=", "<<=", ">>=", "??", "==", "!=", "<>", "<=", ">=", "=>",
+    "+=", "-=", "*=", "/=", "%=", ".=", "&=", "|=", "^=", "**", "<<", ">>", "&&", "||",
+];
+
+/// Sequences of operators that are never valid, alongside what was most likely meant.
+/// These are checked before any operator, so the error points at the whole sequence rather
+/// than lexing it as operators that misparse, eg: `??->` is not `??` followed by `->`.
+const INVALID_OPERATORS: &[(&str, &str)] = &[
+    ("??->", "did you mean the nullsafe operator `?->`?"),
+    ("=>=", "did you mean `>=`?"),
+];
+
+/// What the cursor needs to know about the lexer to eat the next token.
+struct Context<'k> {
+    /// Whether the previous significant token ended an operand,
+    /// which decides whether `.5` is a float or a concatenation.
+    after_operand: bool,
+    /// Whether the cursor is outside of PHP tags, so text up to the next open tag is inline HTML.
+    in_html: bool,
+    /// How text outside of PHP tags is lexed.
+    html_mode: HtmlMode,
+    /// Whether the previous significant token was a `$`, so a keyword is the name of a variable,
+    /// eg: `$class` or `$print`.
+    after_sigil: bool,
+    /// Whether line breaks are separated from horizontal whitespace.
+    split_whitespace: bool,
+    /// The version of PHP the script is lexed against.
+    target: PhpVersion,
+    /// Whether a trailing line break ends the statement before it, see `Lexer::expression_mode`.
+    newline_ends_statement: bool,
+    /// Whether keywords, booleans and `null` carry their lowercase form, rather than their source.
+    normalize_keywords: bool,
+    /// Whether an octal literal containing `8` or `9` is lexed as decimal, see `Lexer::lenient_octal`.
+    lenient_octal: bool,
+    /// The words lexed as keywords.
+    keywords: &'k KeywordSet,
+    /// Which chars may be used within identifiers.
+    identifiers: IdentifierStrictness,
+    /// Whether the ASP style and `<script>` tags open and close PHP code, see `Lexer::legacy_tags`.
+    legacy_tags: bool,
 }
 
 /// The basic PHP Lexer, Serves the syntax of PHP 7.3+
 impl Cursor<'_> {
-    fn eat(&mut self) -> Result<Option<Token>, Error> {
+    /// Eats tokens until the end of the current statement, returning the span eaten.
+    /// This stops after a `;`, or a `}` closing a brace opened within the statement, or before
+    /// a `}` closing the enclosing block. Only braces are counted so a missing `)` or `]` does
+    /// not hide the `;`. Chars that do not begin a token are skipped.
+    fn skip_to_statement_boundary(&mut self, context: &Context) -> Span {
+        let start = self.get_pos();
+        let mut depth = 0usize;
+        while !self.is_eof() {
+            let before = self.clone();
+            let kind = match self.eat(context) {
+                Ok(Some(token)) => token.0,
+                _ => continue,
+            };
+
+            match kind {
+                TokenType::LeftBrace => depth += 1,
+                TokenType::RightBrace if depth == 0 => {
+                    *self = before;
+                    break;
+                }
+                TokenType::RightBrace => {
+                    depth -= 1;
+                    if depth == 0 {
+                        break;
+                    }
+                }
+                TokenType::EOS if depth == 0 => break,
+                _ => {}
+            }
+        }
+        Span::new(start, self.get_pos())
+    }
+
+    fn eat(&mut self, context: &Context) -> Result<Option<Token>, Error> {
         let start_pos = self.get_pos();
 
+        if start_pos == 0 {
+            if self.first()? == BYTE_ORDER_MARK {
+                self.peek();
+                return token!(start_pos, self.get_pos(), TokenType::BOM);
+            }
+
+            if self.rest().starts_with("#!") {
+                let line = self.eat_while(|c| c != '\n' && c != '\r')?;
+                return token!(start_pos, self.get_pos(), TokenType::Shebang, Some(line));
+            }
+        }
+
+        if context.in_html {
+            let len = html_len(self.rest(), context.legacy_tags);
+            if len > 0 {
+                let html = self.rest()[..len].to_string();
+                self.peek_inc(html.chars().count() - 1);
+                return token!(start_pos, self.get_pos(), TokenType::InlineHtml, Some(html));
+            }
+        }
+
+        if context.newline_ends_statement && self.rest().trim().is_empty() {
+            let rest = self.rest();
+            let lf = if rest.ends_with("\r\n") {
+                2
+            } else if rest.ends_with('\n') {
+                1
+            } else {
+                0
+            };
+            if lf > 0 {
+                let len = rest.len() - lf;
+                let (kind, text) = if len > 0 {
+                    (TokenType::Whitespace, &rest[..len])
+                } else {
+                    (TokenType::EOS, rest)
+                };
+                self.peek_inc(text.chars().count() - 1);
+                return token!(start_pos, self.get_pos(), kind, Some(text.to_string()));
+            }
+        }
+
+        if context.split_whitespace {
+            if let Some((lf, breaks)) = self.eat_line_breaks()? {
+                return token!(start_pos, self.get_pos(), TokenType::LF(lf), Some(breaks));
+            }
+
+            let spaces = self.eat_while_cursor(|cursor, c| {
+                c.is_whitespace() && c != '\n' && !cursor.rest().starts_with("\r\n")
+            })?;
+            if !spaces.is_empty() {
+                return token!(
+                    start_pos,
+                    self.get_pos(),
+                    TokenType::Whitespace,
+                    Some(spaces)
+                );
+            }
+        }
+
         if let Some(spaces) = self.eat_whitespace()? {
             return token!(
                 start_pos,
@@ -34,10 +190,54 @@ impl Cursor<'_> {
             );
         }
 
-        if let Some(comment) = self.eat_comment()? {
+        if let Some(comment) = self.eat_comment(context)? {
             return token!(start_pos, self.get_pos(), TokenType::Comment, Some(comment));
         }
 
+        let reserved = match context.legacy_tags {
+            true => self.eat_legacy_tag()?,
+            false => None,
+        };
+        let reserved = match reserved {
+            Some(reserved) => Some(reserved),
+            None => self.eat_value_reserved()?,
+        };
+        if let Some((token_type, mut value)) = reserved {
+            if token_type == TokenType::CloseTag && context.html_mode != HtmlMode::Code {
+                for lf in ["\r\n", "\n"] {
+                    if self.rest().starts_with(lf) {
+                        self.peek_inc(lf.len() - 1);
+                        value.push_str(lf);
+                        break;
+                    }
+                }
+            }
+            return token!(start_pos, self.get_pos(), token_type, Some(value));
+        }
+
+        if let Some((variant, body)) = self.eat_heredoc() {
+            return token!(
+                start_pos,
+                self.get_pos(),
+                TokenType::StringLit(variant),
+                Some(body)
+            );
+        }
+
+        if let Some(&(operator, hint)) = INVALID_OPERATORS
+            .iter()
+            .find(|(operator, _)| self.rest().starts_with(operator))
+        {
+            // the sequence is eaten, so lexing may resume after it.
+            self.peek_inc(operator.len() - 1);
+            return Err(LexError::InvalidOperator {
+                operator,
+                span: start_pos..start_pos + operator.len(),
+                hint,
+            }
+            .into());
+        }
+
         if let Some(operator) = self.eat_operator()? {
             return token!(
                 start_pos,
@@ -47,15 +247,38 @@ impl Cursor<'_> {
             );
         }
 
-        if let Some(keyword) = self.eat_keyword()? {
-            return token!(start_pos, self.get_pos(), TokenType::Keyword(keyword), None);
+        if context.after_sigil {
+            if let Some(identifier) = self.eat_identifier(context.identifiers)? {
+                return token!(
+                    start_pos,
+                    self.get_pos(),
+                    TokenType::Identifier,
+                    Some(identifier)
+                );
+            }
+        }
+
+        if let Some((keyword, word)) = self.eat_keyword(context)? {
+            let value = match context.normalize_keywords {
+                true => keyword.as_str().to_string(),
+                false => word,
+            };
+            return token!(
+                start_pos,
+                self.get_pos(),
+                TokenType::Keyword(keyword),
+                Some(value)
+            );
         }
 
-        if let Some(boolean) = self.eat_boolean()? {
-            return token!(start_pos, self.get_pos(), TokenType::Boolean, Some(boolean));
+        if let Some((token_type, mut word)) = self.eat_literal_keyword(context.identifiers) {
+            if context.normalize_keywords {
+                word.make_ascii_lowercase();
+            }
+            return token!(start_pos, self.get_pos(), token_type, Some(word));
         }
 
-        if let Some(identifier) = self.eat_identifier()? {
+        if let Some(identifier) = self.eat_identifier(context.identifiers)? {
             return token!(
                 start_pos,
                 self.get_pos(),
@@ -64,8 +287,13 @@ impl Cursor<'_> {
             );
         }
 
-        if let Some(n) = self.eat_number()? {
-            return token!(start_pos, self.get_pos(), TokenType::NumericalLit(n));
+        if let Some((n, literal)) = self.eat_number(context)? {
+            return token!(
+                start_pos,
+                self.get_pos(),
+                TokenType::NumericalLit(n),
+                Some(literal)
+            );
         }
 
         if let Some((var, string)) = self.eat_string()? {
@@ -78,153 +306,271 @@ impl Cursor<'_> {
             );
         }
 
-        if let Some(token_type) = self.eat_value_reserved()? {
-            return token!(start_pos, self.get_pos(), token_type.0, Some(token_type.1));
-        }
-
         if let Some(token_type) = self.eat_reserved()? {
             // Peek if a reserved character is found
             self.peek();
             return token!(start_pos, self.get_pos(), token_type);
         }
 
-        self.peek();
-        return Err(Error::new(
-            ErrorKind::InvalidData,
-            format!(
-                "Failed to parse a token from buffer: {} until {}",
-                start_pos,
-                self.get_pos()
-            ),
-        ));
+        let character = self.peek().unwrap_or(END_OF_FILE);
+        Err(LexError::UnexpectedChar {
+            character,
+            span: start_pos..self.get_pos(),
+            hint: unexpected_char_hint(character, start_pos),
+        }
+        .into())
     }
 
-    fn eat_comment(&mut self) -> Result<Option<String>, Error> {
-        return Ok(match self.first()? {
+    /// Eats a comment, `#[` starts an attribute rather than a comment since PHP 8.0.
+    fn eat_comment(&mut self, context: &Context) -> Result<Option<String>, Error> {
+        Ok(match self.first()? {
+            '#' if self.second()? != '[' || context.target < PhpVersion::PHP_8_0 => {
+                Some(self.eat_line_comment()?)
+            }
             '/' => {
                 // check the next character
                 if self.second()? == '/' {
-                    Some(self.eat_while(|c| c != '\n')?)
+                    Some(self.eat_line_comment()?)
                 } else if self.second()? == '*' {
-                    // eat the comment
-                    let comment = self.eat_while_cursor(|cursor, c| {
-                        if c == '*' {
-                            if cursor.first().unwrap() == '/' {
-                                cursor.eat();
-                                return false;
-                            } else {
-                                return true;
-                            }
-                        } else {
-                            return true;
-                        }
-                    })?;
-                    Some(comment)
+                    Some(self.eat_block_comment())
                 } else {
                     None
                 }
             }
             _ => None,
-        });
+        })
+    }
+
+    /// Eats a `/* */` comment, which ends at the first `*/` after its `/*`, so `/*/` does not
+    /// end itself. An unterminated comment runs until the end of the script, as PHP lexes it,
+    /// even when the script ends with a `*`.
+    fn eat_block_comment(&mut self) -> String {
+        let rest = self.rest();
+        let len = rest[2..].find("*/").map_or(rest.len(), |end| end + 4);
+        self.peek_inc(rest[..len].chars().count() - 1);
+        rest[..len].to_string()
+    }
+
+    /// Eats a `//` or `#` comment, which ends at the end of its line or before a `?>`,
+    /// as PHP closes its tag there, eg: `<?php // note ?> html`.
+    fn eat_line_comment(&mut self) -> Result<String, Error> {
+        self.eat_while_cursor(|cursor, c| c != '\n' && !cursor.rest().starts_with("?>"))
     }
 
     /// This may be misleading,
     /// because it eats ALL whitespace until a char is not whitespace
     fn eat_whitespace(&mut self) -> Result<Option<String>, Error> {
         let segment = self.eat_while(|c| c.is_whitespace())?;
-        return if segment.is_empty() {
+        if segment.is_empty() {
             Ok(None)
         } else {
             Ok(Some(segment))
-        };
+        }
     }
 
-    fn eat_identifier(&mut self) -> Result<Option<String>, Error> {
-        Ok(match self.first()? {
-            // 'A'..='z' can't be used here as it includes a plethora of reserved characters that are used elsewhere
-            '_' | 'a'..='z' | 'A'..='Z' => Some(
-                self.eat_while(|c: char| !c.is_whitespace() && (c.is_alphanumeric() || c == '_'))?,
-            ),
-            _ => None,
-        })
+    /// Eats consecutive line breaks, including any horizontal whitespace on the blank lines
+    /// between them, the kind of line break is that of the first one.
+    fn eat_line_breaks(&mut self) -> Result<Option<(LF, String)>, Error> {
+        let lf = if self.rest().starts_with("\r\n") {
+            LF::CRLF
+        } else if self.rest().starts_with('\n') {
+            LF::LF
+        } else {
+            return Ok(None);
+        };
+
+        let mut breaks = String::new();
+        loop {
+            let rest = self.rest();
+            let indent = rest.len() - rest.trim_start_matches([' ', '\t']).len();
+            let after = &rest[indent..];
+            let len = if after.starts_with("\r\n") {
+                2
+            } else if after.starts_with('\n') {
+                1
+            } else {
+                break;
+            };
+
+            breaks.push_str(&rest[..indent + len]);
+            self.peek_inc(indent + len - 1);
+        }
+        Ok(Some((lf, breaks)))
     }
 
-    fn eat_number(&mut self) -> Result<Option<Numeric>, Error> {
-        Ok(match self.first()? {
-            // there is an issue with leading floats where they are parsed as accessors right now.
-            // we should leave this to the parser.
-            '0'..='9' => {
-                // do this in the background,
-                // todo ACTUALLY IMPLEMENT THIS
-                self.eat_while(|c: char| c.is_digit(10) || c == '.');
-                Some(Numeric::Int(0))
-            }
-            _ => None,
+    fn eat_identifier(&mut self, rules: IdentifierStrictness) -> Result<Option<String>, Error> {
+        Ok(match rules.is_start(self.first()?) {
+            true => Some(self.eat_while(|c| rules.is_part(c))?),
+            false => None,
         })
     }
 
-    /// Eats a keyword but does not parse it.
-    fn eat_keyword(&mut self) -> Result<Option<Keyword>, Error> {
-        let mut segment = String::new();
-        for i in 0..MAX_KEYWORD_LENGTH {
-            segment.push(self.nth_char(i)?);
+    /// Eats a numeric literal, returning it alongside the literal it was written as,
+    /// eg: `1`, `1.5`, `1e3`, `0x1F`, `0b101`, `0o17` or `1_000`.
+    /// A leading dot, eg: `.5`, is only a float when it does not follow an operand,
+    /// otherwise `$a.5` is the concatenation of `$a` and `5`.
+    /// An underscore may only separate two digits, and a literal must not be directly followed
+    /// by an identifier char, eg: `1__0`, `1_`, `0x` and `1e` are invalid.
+    ///
+    /// An integer with a leading zero is octal, eg: `0755`, unless it has a fraction or an
+    /// exponent, eg: `0755.5` is a float. Such an integer containing `8` or `9` is invalid.
+    fn eat_number(&mut self, context: &Context) -> Result<Option<(Numeric, String)>, Error> {
+        let leading_dot = self.first()? == '.' && self.second()?.is_ascii_digit();
+        if !self.first()?.is_ascii_digit() && (!leading_dot || context.after_operand) {
+            return Ok(None);
+        }
+
+        let start = self.get_pos();
+        let mut literal = String::new();
+        let radix = match (self.first()?, self.second()?.to_ascii_lowercase()) {
+            ('0', 'x') => 16,
+            ('0', 'b') => 2,
+            ('0', 'o') => 8,
+            _ => 10,
+        };
+
+        let n = if radix != 10 {
+            literal.push(self.peek().unwrap_or(END_OF_FILE));
+            literal.push(self.peek().unwrap_or(END_OF_FILE));
+            let digits = self.eat_digits(radix, &mut literal)?;
+            if digits.is_empty() {
+                return Err(self.invalid_number(start));
+            }
+            radix_value(&digits, radix)
+        } else {
+            let mut number = self.eat_digits(10, &mut literal)?;
+            let mut float = false;
+            if self.first()? == '.' {
+                self.peek();
+                literal.push('.');
+                number.push('.');
+                number.push_str(&self.eat_digits(10, &mut literal)?);
+                float = true;
+            }
+            let sign = matches!(self.second()?, '+' | '-');
+            if matches!(self.first()?, 'e' | 'E')
+                && self.nth_char(if sign { 2 } else { 1 })?.is_ascii_digit()
+            {
+                literal.push(self.peek().unwrap_or(END_OF_FILE));
+                number.push('e');
+                if sign {
+                    let sign = self.peek().unwrap_or(END_OF_FILE);
+                    literal.push(sign);
+                    number.push(sign);
+                }
+                number.push_str(&self.eat_digits(10, &mut literal)?);
+                float = true;
+            }
 
-            if let Ok(keyword) = Keyword::from_str(&segment) {
-                if self.nth_char(i + 1)?.is_whitespace() {
-                    self.peek_inc(i);
-                    return Ok(Some(keyword));
+            if float {
+                Numeric::Float(number.parse().unwrap_or_default())
+            } else if number.len() > 1 && number.starts_with('0') {
+                if number.contains(['8', '9']) {
+                    if !context.lenient_octal {
+                        return Err(self.invalid_number(start));
+                    }
+                    radix_value(&number, 10)
                 } else {
-                    return Ok(None);
+                    radix_value(&number[1..], 8)
                 }
+            } else if let Ok(i) = number.parse() {
+                Numeric::Int(i)
+            } else if let Ok(i) = number.parse() {
+                Numeric::LInt(i)
+            } else {
+                Numeric::Float(number.parse().unwrap_or_default())
             }
+        };
+
+        if context.identifiers.is_part(self.first()?) {
+            return Err(self.invalid_number(start));
         }
+        Ok(Some((n, literal)))
+    }
 
-        return Ok(None);
+    /// Eats the digits of the given radix and the underscores separating them, pushing them to
+    /// the literal. Returns the digits without their separators.
+    fn eat_digits(&mut self, radix: u32, literal: &mut String) -> Result<String, Error> {
+        let mut digits = String::new();
+        loop {
+            let c = self.first()?;
+            if c.is_digit(radix) {
+                digits.push(c);
+            } else if !(c == '_' && !digits.is_empty() && self.second()?.is_digit(radix)) {
+                return Ok(digits);
+            }
+            literal.push(c);
+            self.peek();
+        }
+    }
+
+    /// Eats the rest of a malformed numeric literal that began at `start`.
+    fn invalid_number(&mut self, start: usize) -> Error {
+        let _ = self.eat_while(is_identifier_char);
+        LexError::InvalidNumber {
+            span: start..self.get_pos(),
+        }
+        .into()
+    }
+
+    /// Eats a keyword, returning it alongside the word it was written as.
+    /// Keywords are case insensitive, eg: `FUNCTION` is the `function` keyword.
+    /// A keyword must not be directly followed by an identifier char, eg: `classes` is an identifier.
+    fn eat_keyword(&mut self, context: &Context) -> Result<Option<(Keyword, String)>, Error> {
+        let word: String = self
+            .chars()
+            .take_while(|c| context.identifiers.is_part(*c))
+            .take(context.keywords.max_len() + 1)
+            .collect();
+
+        if word.is_empty() {
+            return Ok(None);
+        }
+
+        Ok(match context.keywords.get(&word) {
+            Some(keyword) => {
+                self.peek_inc(word.chars().count() - 1);
+                Some((keyword, word))
+            }
+            None => None,
+        })
     }
 
     fn eat_operator(&mut self) -> Result<Option<String>, Error> {
+        if let Some(operator) = OPERATORS.iter().find(|op| self.rest().starts_with(*op)) {
+            self.peek_inc(operator.len() - 1);
+            return Ok(Some(operator.to_string()));
+        }
+
         Ok(match self.first()? {
-            '+' | '-' | '*' | '/' | '%' | '=' | '<' | '>' | '&' | '|' | '^' | '~' => {
+            '+' | '-' | '*' | '/' | '%' | '=' | '<' | '>' | '!' | '&' | '|' | '^' | '~' | '@' => {
                 self.peek();
                 Some(self.get_prev().to_string())
             }
-            'o' => {
-                if self.nth_char(1)? == 'r' {
-                    self.peek_inc(2);
-                    Some("or".to_string())
-                } else {
-                    None
-                }
-            }
-            'a' => {
-                if self.nth_char(1)? == 'n' && self.nth_char(2)? == 'd' {
-                    self.peek_inc(3);
-                    Some("and".to_string())
-                } else {
-                    None
-                }
-            }
             _ => None,
         })
     }
 
-    fn eat_boolean(&mut self) -> Result<Option<String>, Error> {
-        // there is probably a better way to do this.
-        let mut segment = String::new();
-        for i in 0..4 {
-            segment.push(self.nth_char(i)?);
-
-            if segment == "true" || segment == "false" {
-                self.peek_inc(i);
-                return Ok(Some(segment));
-            }
-        }
-        return Ok(None);
+    /// Eats `true`, `false` or `null`, which are case insensitive.
+    /// Like keywords, these must not be directly followed by an identifier char.
+    fn eat_literal_keyword(&mut self, rules: IdentifierStrictness) -> Option<(TokenType, String)> {
+        let word: String = self
+            .chars()
+            .take_while(|c| rules.is_part(*c))
+            .take(6)
+            .collect();
+        let token_type = match word.to_ascii_lowercase().as_str() {
+            "true" | "false" => TokenType::Boolean,
+            "null" => TokenType::Null,
+            _ => return None,
+        };
+        self.peek_inc(word.len() - 1);
+        Some((token_type, word))
     }
 
     fn eat_string(&mut self) -> Result<Option<(StringType, String)>, Error> {
-        if self.first()? != '"' && self.first()? != '\'' && self.first()? != '`' {
-            return Ok(None);
+        if self.first()? != '"' && self.first()? != '\'' {
+            Ok(None)
         } else {
             let first = self.peek().unwrap();
             let variant = match first {
@@ -232,12 +578,227 @@ impl Cursor<'_> {
                 '\'' => StringType::Single,
                 _ => unreachable!(),
             };
-            return Ok(Some((variant, self.eat_while(|c| c != first)?)));
+            let value = match variant {
+                StringType::Single => self.eat_single_quoted()?,
+                _ => self.eat_double_quoted()?,
+            };
+            Ok(Some((variant, value)))
+        }
+    }
+
+    /// Eats a heredoc or nowdoc, returning its body with the indentation of its closing
+    /// identifier removed from every line. Escapes within a heredoc are not decoded.
+    /// The body is scanned line by line, so long heredocs are lexed in linear time.
+    fn eat_heredoc(&mut self) -> Option<(StringType, String)> {
+        let rest = self.rest();
+        let header = rest.strip_prefix("<<<")?.trim_start_matches([' ', '\t']);
+        let (variant, quote) = match header.chars().next()? {
+            '\'' => (StringType::NowDoc, "'"),
+            '"' => (StringType::HereDoc, "\""),
+            _ => (StringType::HereDoc, ""),
+        };
+
+        let label = &header[quote.len()..];
+        let len = label.len() - label.trim_start_matches(is_identifier_char).len();
+        let label = &label[..len];
+        if label.is_empty() || label.starts_with(|c: char| c.is_ascii_digit()) {
+            return None;
+        }
+
+        let after = header[quote.len() + len..].strip_prefix(quote)?;
+        let after = after
+            .strip_prefix("\r\n")
+            .or_else(|| after.strip_prefix('\n'))?;
+        let body_start = rest.len() - after.len();
+
+        let mut line_start = body_start;
+        let (body_end, indent, end) = loop {
+            let line_end = rest[line_start..].find('\n').map(|end| line_start + end);
+            let line = &rest[line_start..line_end.unwrap_or(rest.len())];
+            let trimmed = line.trim_start_matches([' ', '\t']);
+            if trimmed
+                .strip_prefix(label)
+                .is_some_and(|after| !after.starts_with(is_identifier_char))
+            {
+                let indent = line.len() - trimmed.len();
+                break (line_start, indent, line_start + indent + label.len());
+            }
+
+            match line_end {
+                Some(line_end) => line_start = line_end + 1,
+                // an unterminated heredoc runs until the end of the script.
+                None => break (rest.len(), 0, rest.len()),
+            }
+        };
+
+        let body = &rest[body_start..body_end];
+        let body = body
+            .strip_suffix("\r\n")
+            .or_else(|| body.strip_suffix('\n'))
+            .unwrap_or(body);
+        let body = body
+            .split('\n')
+            .map(|line| {
+                let ws = line.len() - line.trim_start_matches([' ', '\t']).len();
+                &line[ws.min(indent)..]
+            })
+            .collect::<Vec<&str>>()
+            .join("\n");
+
+        self.peek_inc(rest[..end].chars().count() - 1);
+        Some((variant, body))
+    }
+
+    /// Eats the contents of a single quoted string, up to its closing quote.
+    /// Only `\\` and `\'` are escapes, any other backslash is kept as is, eg: `'\n'` is `\n`.
+    fn eat_single_quoted(&mut self) -> Result<String, Error> {
+        let mut value = String::new();
+        while !self.is_eof() && self.first()? != '\'' {
+            let c = self.peek().unwrap_or(END_OF_FILE);
+            if c == '\\' && matches!(self.first()?, '\\' | '\'') {
+                value.push(self.peek().unwrap_or(END_OF_FILE));
+            } else {
+                value.push(c);
+            }
+        }
+        Ok(value)
+    }
+
+    /// Eats the contents of a double quoted string, up to its closing quote, decoding escapes.
+    /// Octal and hex escapes above `\x7F` are bytes, which are decoded as latin-1 chars.
+    /// Unknown escapes are kept as is, eg: `"\q"` is `\q`. A `{$...}` interpolation is kept
+    /// as is, and may contain braces and quotes, eg: `"{$a["}"]}"`.
+    fn eat_double_quoted(&mut self) -> Result<String, Error> {
+        let mut value = String::new();
+        while !self.is_eof() && self.first()? != '"' {
+            if let Some(len) = interpolation::braced_len(self.rest()) {
+                let expression = &self.rest()[..len];
+                value.push_str(expression);
+                self.peek_inc(expression.chars().count() - 1);
+                continue;
+            }
+
+            let start = self.get_pos();
+            let c = self.peek().unwrap_or(END_OF_FILE);
+            if c != '\\' {
+                value.push(c);
+                continue;
+            }
+
+            let escaped = match self.first()? {
+                'n' => '\n',
+                't' => '\t',
+                'r' => '\r',
+                'v' => '\u{b}',
+                'e' => '\u{1b}',
+                'f' => '\u{c}',
+                '\\' | '$' | '"' => self.first()?,
+                '0'..='7' => {
+                    let digits: String =
+                        self.chars().take_while(|c| c.is_digit(8)).take(3).collect();
+                    self.peek_inc(digits.len() - 1);
+                    let byte = u32::from_str_radix(&digits, 8).unwrap_or_default() & 0xFF;
+                    value.push(char::from_u32(byte).unwrap_or_default());
+                    continue;
+                }
+                'x' if self.second()?.is_ascii_hexdigit() => {
+                    self.peek();
+                    let digits: String = self
+                        .chars()
+                        .take_while(|c| c.is_ascii_hexdigit())
+                        .take(2)
+                        .collect();
+                    self.peek_inc(digits.len() - 1);
+                    let byte = u32::from_str_radix(&digits, 16).unwrap_or_default();
+                    value.push(char::from_u32(byte).unwrap_or_default());
+                    continue;
+                }
+                'u' if self.second()? == '{' => {
+                    let rest = self.rest();
+                    let digits = rest[2..].len()
+                        - rest[2..]
+                            .trim_start_matches(|c: char| c.is_ascii_hexdigit())
+                            .len();
+                    let closed = rest[2 + digits..].starts_with('}');
+                    let code_point = u32::from_str_radix(&rest[2..2 + digits], 16)
+                        .ok()
+                        .filter(|_| closed)
+                        .and_then(char::from_u32);
+                    match code_point {
+                        Some(code_point) => {
+                            self.peek_inc(2 + digits);
+                            value.push(code_point);
+                            continue;
+                        }
+                        None => {
+                            let end = start + 3 + digits + closed as usize;
+                            return Err(LexError::InvalidEscape { span: start..end }.into());
+                        }
+                    }
+                }
+                _ => {
+                    value.push(c);
+                    continue;
+                }
+            };
+            self.peek();
+            value.push(escaped);
         }
+        Ok(value)
+    }
+
+    /// Eats the ASP style `<%`, `<%=` and `%>` tags, or a `<script language="php">` tag and the
+    /// `</script>` closing it, see `Lexer::legacy_tags`.
+    fn eat_legacy_tag(&mut self) -> Result<Option<(TokenType, String)>, Error> {
+        let rest = self.rest();
+        let (token_type, len) = if rest.starts_with("<%=") {
+            (TokenType::OpenTag, 3)
+        } else if rest.starts_with("<%") {
+            (TokenType::OpenTag, 2)
+        } else if rest.starts_with("%>") {
+            (TokenType::CloseTag, 2)
+        } else if let Some(len) = script_tag_len(rest) {
+            (TokenType::OpenTag, len)
+        } else if let Some(len) = script_close_tag_len(rest) {
+            (TokenType::CloseTag, len)
+        } else {
+            return Ok(None);
+        };
+
+        let tag = rest[..len].to_string();
+        self.peek_inc(tag.chars().count() - 1);
+        Ok(Some((token_type, tag)))
     }
 
     fn eat_value_reserved(&mut self) -> Result<Option<(TokenType, String)>, Error> {
         Ok(match self.first()? {
+            '<' if self.second()? == '?' => {
+                let len = match self.rest().get(2..5) {
+                    Some(php)
+                        if php.eq_ignore_ascii_case("php")
+                            && !is_identifier_char(self.nth_char(5)?) =>
+                    {
+                        5
+                    }
+                    _ if self.nth_char(2)? == '=' => 3,
+                    _ => 2,
+                };
+                let tag = self.rest()[..len].to_string();
+                self.peek_inc(len - 1);
+                Some((TokenType::OpenTag, tag))
+            }
+            '?' if self.second()? == '>' => {
+                self.peek_inc(1);
+                Some((TokenType::CloseTag, "?>".to_string()))
+            }
+            '.' if self.rest().starts_with("...") => {
+                self.peek_inc(2);
+                Some((TokenType::Ellipsis, "...".to_string()))
+            }
+            '#' if self.second()? == '[' => {
+                self.peek_inc(1);
+                Some((TokenType::AttributeStart, "#[".to_string()))
+            }
             ':' => {
                 if self.second()? == ':' {
                     self.peek_inc(1);
@@ -250,6 +811,20 @@ impl Cursor<'_> {
                     Some((TokenType::Colon, ":".to_string()))
                 }
             }
+            '-' if self.second()? == '>' => {
+                self.peek_inc(1);
+                Some((
+                    TokenType::Accessor(AccessType::ReferenceMember),
+                    "->".to_string(),
+                ))
+            }
+            '?' if self.second()? == '-' && self.nth_char(2)? == '>' => {
+                self.peek_inc(2);
+                Some((
+                    TokenType::Accessor(AccessType::NullsafeMember),
+                    "?->".to_string(),
+                ))
+            }
             _ => None,
         })
     }
@@ -273,22 +848,872 @@ impl Cursor<'_> {
     }
 }
 
+/// Which comments the lexer emits as `Comment` tokens, see `Lexer::comments`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CommentPolicy {
+    /// Every comment is emitted.
+    #[default]
+    Retain,
+    /// Line comments, starting with `//` or `#`, are dropped while block comments are kept.
+    DropLine,
+    /// No comment is emitted.
+    DropAll,
+    /// Only doc comments, starting with `/**`, are emitted.
+    DocOnly,
+}
+
+impl CommentPolicy {
+    /// Whether the comment, including its markers, is emitted under this policy.
+    pub fn retains(&self, comment: &str) -> bool {
+        match self {
+            CommentPolicy::Retain => true,
+            CommentPolicy::DropLine => comment.starts_with("/*"),
+            CommentPolicy::DropAll => false,
+            CommentPolicy::DocOnly => comment.starts_with("/**") && comment != "/**/",
+        }
+    }
+}
+
+/// How the lexer treats text outside of PHP tags, see `Lexer::html_mode`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HtmlMode {
+    /// The whole script is lexed as PHP, including any text outside of tags.
+    #[default]
+    Code,
+    /// Text outside of tags is lexed as a single `InlineHtml` token.
+    Opaque,
+    /// Text outside of tags is skipped, as if it were not in the script.
+    Skip,
+}
+
+/// Which chars may be used within identifiers, see `Lexer::identifier_strictness`.
+/// Every rule allows `_` and ASCII letters and digits, although an identifier may not start
+/// with a digit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum IdentifierStrictness {
+    /// Only `_` and ASCII letters and digits, eg: `$café` is an error.
+    Ascii,
+    /// Any char that is not ASCII as well, as PHP allows any byte from `0x80` to `0xFF`,
+    /// so an identifier may start with, or contain, any multibyte UTF-8 char, eg: `$été`.
+    PhpBytes,
+    /// Any alphanumeric char after the start, which must be ASCII, eg: `$café` but not `$été`.
+    #[default]
+    Unicode,
+}
+
+impl IdentifierStrictness {
+    /// Whether an identifier may start with the char.
+    pub fn is_start(self, c: char) -> bool {
+        c == '_' || c.is_ascii_alphabetic() || (self == Self::PhpBytes && !c.is_ascii())
+    }
+
+    /// Whether the char may be used within an identifier, after its first char.
+    pub fn is_part(self, c: char) -> bool {
+        match self {
+            Self::Ascii => c == '_' || c.is_ascii_alphanumeric(),
+            Self::PhpBytes => c == '_' || c.is_ascii_alphanumeric() || !c.is_ascii(),
+            Self::Unicode => is_identifier_char(c),
+        }
+    }
+}
+
 pub struct Lexer<'a> {
-    cursor: Cursor<'a>,
+    script: Cow<'a, str>,
+    /// The byte range of the script that is lexed.
+    range: Range<usize>,
+    /// The byte offset the next token starts at.
+    pos: usize,
+    /// The line and column the next token starts at.
+    location: Location,
+    /// The index of the next token.
+    index: usize,
+    /// Whether line breaks are lexed separately from horizontal whitespace.
+    split_whitespace: bool,
+    /// Whether the script is a lone expression, see `Lexer::expression_mode`.
+    expression_mode: bool,
+    /// Whether keyword, boolean and `null` values are lowercased.
+    normalize_keywords: bool,
+    /// Whether octal literals containing `8` or `9` are lexed as decimal.
+    lenient_octal: bool,
+    /// Which chars may be used within identifiers.
+    identifiers: IdentifierStrictness,
+    /// The words lexed as keywords, see `Lexer::keywords`.
+    keywords: KeywordSet,
+    /// Which comments are emitted.
+    comments: CommentPolicy,
+    /// How text outside of PHP tags is lexed.
+    html_mode: HtmlMode,
+    /// Whether the next token is outside of PHP tags.
+    html: bool,
+    /// Whether deprecated lexical constructs should be reported.
+    warn_deprecated: bool,
+    /// Whether tabs within whitespace should be reported.
+    forbid_tabs: bool,
+    /// Whether valid sequences that are most likely typos should be reported.
+    warn_typos: bool,
+    warnings: Vec<LexWarning>,
+    /// The statistics collected so far, if enabled with `Lexer::collect_stats`.
+    stats: Option<LexStats>,
+    /// The version of PHP the script is lexed against.
+    target: PhpVersion,
+    /// Whether the ASP style and `<script>` tags open and close PHP code.
+    legacy_tags: bool,
+    /// The last two significant (non whitespace or comment) tokens, the most recent first.
+    recent: [Option<TokenType>; 2],
+    /// The currently open parenthesis, `true` if it opened a parameter list or closure `use` list.
+    parens: Vec<bool>,
+    /// The currently open brackets, `true` if it opened an attribute group.
+    brackets: Vec<bool>,
+    /// The currently open braces, `true` if it opened the hooks of a property.
+    braces: Vec<bool>,
+    /// The start of the most recent comma.
+    last_comma: usize,
+    /// The amount of brackets, parenthesis and braces currently open.
+    depth: usize,
+    max_input_len: usize,
+    max_depth: usize,
 }
 
 impl<'a> Lexer<'a> {
     pub fn new(script: &'a str) -> Self {
+        Self::from_script(Cow::Borrowed(script))
+    }
+
+    fn from_script(script: Cow<'a, str>) -> Self {
         Self {
-            cursor: Cursor::new(script),
+            range: 0..script.len(),
+            script,
+            pos: 0,
+            location: Location::START,
+            index: 0,
+            split_whitespace: false,
+            expression_mode: false,
+            normalize_keywords: false,
+            lenient_octal: false,
+            identifiers: IdentifierStrictness::default(),
+            keywords: KeywordSet::default(),
+            comments: CommentPolicy::default(),
+            html_mode: HtmlMode::default(),
+            html: false,
+            warn_deprecated: false,
+            forbid_tabs: false,
+            warn_typos: false,
+            warnings: Vec::new(),
+            stats: None,
+            target: PhpVersion::default(),
+            legacy_tags: false,
+            recent: [None, None],
+            parens: Vec::new(),
+            brackets: Vec::new(),
+            braces: Vec::new(),
+            last_comma: 0,
+            depth: 0,
+            max_input_len: usize::MAX,
+            max_depth: usize::MAX,
+        }
+    }
+
+    /// Lexes only the bytes `start..end` of the script, while the ranges and locations of
+    /// tokens remain relative to the whole script. Fails with `LexError::InvalidRange` if the
+    /// range is out of bounds or does not start and end on char boundaries.
+    pub fn new_range(script: &'a str, start: usize, end: usize) -> Result<Self, Error> {
+        if start > end || !script.is_char_boundary(start) || !script.is_char_boundary(end) {
+            return Err(LexError::InvalidRange { span: start..end }.into());
         }
+
+        let mut lexer = Self::new(script);
+        lexer.range = start..end;
+        lexer.reset();
+        Ok(lexer)
+    }
+
+    /// Reads the script at `path`, the lexer owns the script so it is not bound to a lifetime.
+    /// Fails with `LexError::InvalidUtf8` if the script is not valid UTF-8.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Lexer<'static>, Error> {
+        let bytes = fs::read(path)?;
+        let len = bytes.len();
+        let script = String::from_utf8(bytes).map_err(|e| invalid_utf8(e.utf8_error(), len))?;
+        Ok(Lexer::from_script(Cow::Owned(script)))
+    }
+
+    /// Validates the script is UTF-8, failing with `LexError::InvalidUtf8` positioned at
+    /// the first invalid byte sequence.
+    pub fn from_bytes(bytes: &'a [u8]) -> Result<Self, Error> {
+        let script = std::str::from_utf8(bytes).map_err(|e| invalid_utf8(e, bytes.len()))?;
+        Ok(Self::new(script))
+    }
+
+    /// Decodes the script as latin-1, where every byte is a char.
+    /// As PHP scripts are byte oriented this never fails, although byte offsets into the
+    /// lexed script may differ from those of `bytes` for any byte above `0x7F`.
+    pub fn from_latin1(bytes: &[u8]) -> Lexer<'static> {
+        let script = bytes.iter().map(|b| *b as char).collect();
+        Lexer::from_script(Cow::Owned(script))
+    }
+
+    /// The script being lexed.
+    pub fn script(&self) -> &str {
+        &self.script
+    }
+
+    /// Sets the version of PHP the script is lexed against, defaults to `PhpVersion::LATEST`.
+    pub fn target(mut self, version: PhpVersion) -> Self {
+        self.target = version;
+        self
+    }
+
+    /// Limits the length of scripts, in bytes, that may be lexed.
+    /// Lexing a longer script fails with `LexError::LimitExceeded`.
+    pub fn max_input_len(mut self, len: usize) -> Self {
+        self.max_input_len = len;
+        self
+    }
+
+    /// Limits how deeply brackets, parenthesis and braces may be nested.
+    /// Exceeding this fails with `LexError::LimitExceeded`.
+    pub fn max_nesting_depth(mut self, depth: usize) -> Self {
+        self.max_depth = depth;
+        self
+    }
+
+    /// Lexes the script as a lone expression or statement, eg: a line entered into a REPL.
+    /// The script is lexed as PHP from its start, without requiring an open tag, and a
+    /// trailing line break is lexed as the `EOS` ending the statement.
+    pub fn expression_mode(mut self) -> Self {
+        self.expression_mode = true;
+        self
+    }
+
+    /// Lowercases the values of keywords, booleans and `null`,
+    /// eg: `FUNCTION` has the value `function`.
+    /// Their ranges are kept, so `Token::source` still yields the casing they were written in.
+    pub fn normalize_keywords(mut self) -> Self {
+        self.normalize_keywords = true;
+        self
+    }
+
+    /// Lexes an octal literal containing `8` or `9` as decimal rather than failing,
+    /// eg: `08` is `8`, as PHP 5 and below did not reject them.
+    pub fn lenient_octal(mut self) -> Self {
+        self.lenient_octal = true;
+        self
+    }
+
+    /// Sets which chars may be used within identifiers, eg: `IdentifierStrictness::Ascii` to
+    /// reject `$café`. Defaults to `IdentifierStrictness::Unicode`.
+    pub fn identifier_strictness(mut self, strictness: IdentifierStrictness) -> Self {
+        self.identifiers = strictness;
+        self
+    }
+
+    /// Lexes keywords from the given set rather than the built-in `Keyword` table, for
+    /// dialects of PHP that add or remove keywords. Words removed from the set lex as identifiers.
+    pub fn keywords(mut self, keywords: KeywordSet) -> Self {
+        self.keywords = keywords;
+        self
+    }
+
+    /// Sets which comments are emitted, the others are skipped as if they were not in the
+    /// script. Every comment is emitted by default.
+    pub fn comments(mut self, policy: CommentPolicy) -> Self {
+        self.comments = policy;
+        self
+    }
+
+    /// Sets how text outside of PHP tags is lexed, eg: the HTML of a template.
+    /// By default the whole script is lexed as PHP, otherwise the script starts outside of tags.
+    pub fn html_mode(mut self, mode: HtmlMode) -> Self {
+        self.html_mode = mode;
+        self.html = mode != HtmlMode::Code;
+        self
     }
+
+    /// Lexes line breaks as `LF` tokens, separately from the horizontal whitespace lexed as
+    /// `Whitespace`. Consecutive line breaks share a token, see `Token::blank_lines`.
+    pub fn split_whitespace(mut self) -> Self {
+        self.split_whitespace = true;
+        self
+    }
+
+    /// Enables collecting warnings for lexical constructs that are deprecated, removed or not
+    /// yet allowed in the target version, such as the ASP style `<% %>` tags, or a trailing
+    /// comma in a parameter list before PHP 8.0. These can be retrieved with `warnings()`.
+    pub fn warn_deprecated(mut self) -> Self {
+        self.warn_deprecated = true;
+        self
+    }
+
+    /// Lexes the ASP style `<% %>` tags and `<script language="php">` blocks, both removed in
+    /// PHP 7.0, as open and close tags, eg: `<%=` is an `OpenTag` like `<?=`. Each of these open
+    /// tags is reported as a deprecated construct, even without `warn_deprecated`.
+    pub fn legacy_tags(mut self) -> Self {
+        self.legacy_tags = true;
+        self
+    }
+
+    /// Enables collecting a `WarningCode::Tab` warning for every tab within whitespace, for
+    /// style guides that forbid indenting with tabs. Tabs within strings and comments are kept.
+    pub fn forbid_tabs(mut self) -> Self {
+        self.forbid_tabs = true;
+        self
+    }
+
+    /// Enables collecting warnings for sequences that are valid but most likely typos, eg: the
+    /// `@@` of `@@foo()`, see `WarningCode::hint` for what was most likely meant.
+    pub fn warn_typos(mut self) -> Self {
+        self.warn_typos = true;
+        self
+    }
+
+    /// The warnings collected so far.
+    pub fn warnings(&self) -> &[LexWarning] {
+        &self.warnings
+    }
+
+    /// Enables collecting statistics about the tokens lexed, such as the deepest nesting,
+    /// while lexing rather than in a second pass. These can be retrieved with `stats()`.
+    pub fn collect_stats(mut self) -> Self {
+        self.stats = Some(LexStats::default());
+        self
+    }
+
+    /// The statistics collected so far, `None` unless enabled with `collect_stats`.
+    pub fn stats(&self) -> Option<&LexStats> {
+        self.stats.as_ref()
+    }
+
     /// Consumes the next possible token(s).
+    /// Returns `None` once the end of the script has been reached.
+    #[allow(clippy::should_implement_trait)]
     pub fn next(&mut self) -> Result<Option<Token>, Error> {
-        if let Some(v) = self.cursor.eat()? {
-            return Ok(Some(v));
+        loop {
+            if self.pos >= self.range.end {
+                return Ok(None);
+            }
+
+            if self.range.len() > self.max_input_len {
+                return Err(LexError::LimitExceeded {
+                    limit: Limit::InputLength,
+                    max: self.max_input_len,
+                    span: self.range.start + self.max_input_len..self.range.end,
+                }
+                .into());
+            }
+
+            self.check_deprecated();
+            self.check_typos();
+
+            let start = self.location;
+            let script = &self.script[..self.range.end];
+            let mut cursor = Cursor::at(script, self.pos, self.location);
+            let context = self.context();
+            let token = cursor.eat(&context);
+            self.pos = cursor.get_pos();
+            self.location = cursor.location();
+
+            let mut token = token?;
+            match &token {
+                Some(Token(TokenType::Comment, _, Some(comment), ..))
+                    if !self.comments.retains(comment) =>
+                {
+                    continue
+                }
+                Some(Token(TokenType::InlineHtml, ..)) if self.html_mode == HtmlMode::Skip => {
+                    self.html = false;
+                    continue;
+                }
+                _ => {}
+            }
+            if let Some(token) = &mut token {
+                token.3 = self.index;
+                token.4 = start..self.location;
+                self.index += 1;
+                self.track(token)?;
+                if let Some(stats) = &mut self.stats {
+                    stats.record(token, self.depth);
+                }
+            }
+
+            return Ok(token);
+        }
+    }
+
+    /// Resets the lexer to the start of the script, or its range, as if it was just created.
+    /// Any options set are kept, while collected warnings and statistics are cleared.
+    pub fn reset(&mut self) {
+        self.pos = self.range.start;
+        self.location = location_at(&self.script, self.range.start);
+        self.index = 0;
+        self.warnings.clear();
+        if let Some(stats) = &mut self.stats {
+            *stats = LexStats::default();
+        }
+        self.recent = [None, None];
+        self.parens.clear();
+        self.brackets.clear();
+        self.braces.clear();
+        self.html = self.html_mode != HtmlMode::Code;
+        self.last_comma = 0;
+        self.depth = 0;
+    }
+
+    /// Moves the lexer to `pos` and `location` within the context `tokens` leave it in, as if it
+    /// had lexed them, eg: within an attribute group. The tokens are not lexed again, and no
+    /// warnings are reported for them. See `incremental::relex`.
+    fn resume(&mut self, tokens: &[Token], pos: usize, location: Location) -> Result<(), Error> {
+        let warnings = self.warnings.len();
+        for token in tokens {
+            self.track(&mut token.clone())?;
+        }
+        self.warnings.truncate(warnings);
+        self.pos = pos;
+        self.location = location;
+        self.index = tokens.len();
+        Ok(())
+    }
+
+    /// Consumes every remaining token in the script.
+    pub fn tokenize(&mut self) -> Result<Vec<Token>, Error> {
+        let mut tokens = Vec::new();
+        while let Some(token) = self.next()? {
+            tokens.push(token);
+        }
+        Ok(tokens)
+    }
+
+    /// Consumes every remaining token in the script, pairing each with its span.
+    pub fn lex_all_with_spans(&mut self) -> Result<Vec<(Token, Span)>, Error> {
+        let mut tokens = Vec::new();
+        while let Some(token) = self.next()? {
+            let span = token.span();
+            tokens.push((token, span));
+        }
+        Ok(tokens)
+    }
+
+    /// Pushes every remaining token into `f`, until the script ends or `f` breaks.
+    /// Unlike `tokenize`, the tokens are never collected.
+    pub fn drive<F: FnMut(Token) -> ControlFlow<()>>(&mut self, mut f: F) -> Result<(), Error> {
+        while let Some(token) = self.next()? {
+            if f(token).is_break() {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// What the cursor needs to know to eat the next token.
+    fn context(&self) -> Context<'_> {
+        Context {
+            after_operand: self.recent[0].as_ref().is_some_and(TokenType::ends_operand),
+            in_html: self.html,
+            html_mode: self.html_mode,
+            after_sigil: self.recent[0] == Some(TokenType::Variable),
+            split_whitespace: self.split_whitespace,
+            target: self.target,
+            newline_ends_statement: self.expression_mode
+                && !matches!(self.recent[0], None | Some(TokenType::EOS)),
+            normalize_keywords: self.normalize_keywords,
+            lenient_octal: self.lenient_octal,
+            keywords: &self.keywords,
+            identifiers: self.identifiers,
+            legacy_tags: self.legacy_tags,
+        }
+    }
+
+    /// Skips the rest of the current statement, for recovering from an error.
+    /// See `Cursor::skip_to_statement_boundary`, the skipped tokens are not indexed.
+    pub fn skip_to_statement_boundary(&mut self) -> Span {
+        let context = self.context();
+        let script = &self.script[..self.range.end];
+        let mut cursor = Cursor::at(script, self.pos, self.location);
+        let span = cursor.skip_to_statement_boundary(&context);
+        self.pos = cursor.get_pos();
+        self.location = cursor.location();
+        self.recent = [None, None];
+        self.parens.clear();
+        self.brackets.clear();
+        self.braces.clear();
+        span
+    }
+
+    /// Keeps track of the context surrounding the tokens that have been lexed.
+    /// A `]` closing an attribute group becomes an `AttributeEnd`, and a `get` or `set`
+    /// beginning a property hook becomes a keyword.
+    fn track(&mut self, token: &mut Token) -> Result<(), Error> {
+        // a token lexed before may already be an `AttributeEnd`, see `Lexer::resume`.
+        let closes = matches!(token.0, TokenType::RightBracket | TokenType::AttributeEnd);
+        if closes && self.brackets.pop() == Some(true) {
+            token.0 = TokenType::AttributeEnd;
+        }
+        if let Some(hook) = self.property_hook(token) {
+            token.0 = TokenType::Keyword(hook);
+            if self.normalize_keywords {
+                token.2 = Some(hook.as_str().to_string());
+            }
+        }
+
+        let kind = token.kind();
+        if self.forbid_tabs && matches!(kind, TokenType::Whitespace | TokenType::LF(_)) {
+            let start = token.1.start;
+            for (i, _) in token.2.as_deref().unwrap_or_default().match_indices('\t') {
+                self.warnings.push(LexWarning {
+                    code: WarningCode::Tab,
+                    span: start + i..start + i + 1,
+                });
+            }
+        }
+        match kind {
+            TokenType::Whitespace | TokenType::LF(_) | TokenType::Comment => return Ok(()),
+            TokenType::LeftBracket
+            | TokenType::AttributeStart
+            | TokenType::LeftBrace
+            | TokenType::LeftParenthesis
+                if self.depth >= self.max_depth =>
+            {
+                return Err(LexError::LimitExceeded {
+                    limit: Limit::NestingDepth,
+                    max: self.max_depth,
+                    span: token.range(),
+                }
+                .into());
+            }
+            _ => {}
+        }
+
+        match kind {
+            TokenType::LeftBracket | TokenType::AttributeStart => {
+                self.brackets.push(kind == TokenType::AttributeStart);
+                self.depth += 1;
+            }
+            TokenType::LeftBrace => {
+                // eg: `public int $x {`, while `$str{0}` is a string offset before PHP 8.0.
+                let hooks = self.target >= PhpVersion::PHP_8_4
+                    && matches!(
+                        &self.recent,
+                        [Some(TokenType::Identifier), Some(TokenType::Variable)]
+                    );
+                self.braces.push(hooks);
+                self.depth += 1;
+            }
+            TokenType::RightBrace => {
+                self.braces.pop();
+                self.depth = self.depth.saturating_sub(1)
+            }
+            TokenType::RightBracket | TokenType::AttributeEnd => {
+                self.depth = self.depth.saturating_sub(1)
+            }
+            TokenType::LeftParenthesis => {
+                let params = matches!(
+                    &self.recent,
+                    [Some(TokenType::Keyword(Keyword::Function | Keyword::Fn)), _]
+                        | [
+                            Some(TokenType::Identifier),
+                            Some(TokenType::Keyword(Keyword::Function))
+                        ]
+                        | [
+                            Some(TokenType::Keyword(Keyword::Use)),
+                            Some(TokenType::RightParenthesis)
+                        ]
+                );
+                self.parens.push(params);
+                self.depth += 1;
+            }
+            TokenType::RightParenthesis => {
+                self.depth = self.depth.saturating_sub(1);
+                let params = self.parens.pop().unwrap_or(false);
+                if params
+                    && matches!(self.recent[0], Some(TokenType::Comma))
+                    && self.warn_deprecated
+                    && self.target < PhpVersion::PHP_8_0
+                {
+                    let end = token.1.start;
+                    self.warnings.push(LexWarning {
+                        code: WarningCode::TrailingComma,
+                        span: self.last_comma..end,
+                    });
+                }
+            }
+            TokenType::Comma => self.last_comma = token.1.start,
+            TokenType::InlineHtml | TokenType::OpenTag => self.html = false,
+            TokenType::CloseTag => self.html = self.html_mode != HtmlMode::Code,
+            _ => {}
+        }
+
+        // the `&` of a function returning by reference is left out, so `function &foo(` opens
+        // a parameter list as `function foo(` does.
+        let by_ref = kind == TokenType::Operator
+            && token.2.as_deref() == Some("&")
+            && matches!(
+                self.recent[0],
+                Some(TokenType::Keyword(Keyword::Function | Keyword::Fn))
+            );
+        if !by_ref {
+            self.recent = [Some(kind), self.recent[0].take()];
+        }
+        Ok(())
+    }
+
+    /// The hook an identifier declares, if it is a `get` or `set` directly within the hooks of
+    /// a property, eg: `get` in `public int $x { get => 1; }`, `final set {}` or `&get {}`.
+    /// Elsewhere these remain identifiers, eg: `function get() {}` or `$this->get()` in a hook.
+    fn property_hook(&self, token: &Token) -> Option<Keyword> {
+        if token.0 != TokenType::Identifier || self.braces.last() != Some(&true) {
+            return None;
+        }
+        let hook = match token.2.as_deref()?.to_ascii_lowercase().as_str() {
+            "get" => Keyword::Get,
+            "set" => Keyword::Set,
+            _ => return None,
+        };
+        matches!(
+            self.recent[0],
+            Some(
+                TokenType::LeftBrace
+                    | TokenType::RightBrace
+                    | TokenType::EOS
+                    | TokenType::AttributeEnd
+                    | TokenType::Operator
+                    | TokenType::Keyword(Keyword::Final)
+            )
+        )
+        .then_some(hook)
+    }
+
+    /// Checks whether a deprecated construct begins at the current position.
+    /// This does not consume anything, the construct is still lexed as usual.
+    /// Deprecated constructs are only reported with `warn_deprecated`, except for legacy tags
+    /// lexed with `legacy_tags`. Curly string offsets are only reported when targeting PHP 8.0
+    /// or later, as they were removed.
+    fn check_deprecated(&mut self) {
+        let start = self.pos;
+        let rest = &self.script[start..self.range.end];
+
+        let found = if !self.warn_deprecated && !self.legacy_tags {
+            None
+        } else if rest.starts_with("<%") || rest.starts_with("%>") {
+            Some((WarningCode::AspTag, 2))
         } else {
-            return Ok(None);
+            script_tag_len(rest).map(|len| (WarningCode::ScriptTag, len))
+        };
+        let found = found.or_else(|| {
+            curly_offset_len(rest)
+                .filter(|_| self.warn_deprecated && self.target >= PhpVersion::PHP_8_0)
+                .map(|len| (WarningCode::CurlyStringOffset, len))
+        });
+
+        if let Some((code, len)) = found {
+            self.warnings.push(LexWarning {
+                code,
+                span: start..start + len,
+            });
+        }
+    }
+
+    /// Checks whether a likely typo begins at the current position, see `Lexer::warn_typos`.
+    /// This does not consume anything, the sequence is still lexed as usual.
+    fn check_typos(&mut self) {
+        let start = self.pos;
+        let rest = &self.script[start..self.range.end];
+
+        // a run of `@` is reported once, from its first `@`.
+        if self.warn_typos && rest.starts_with("@@") && !self.script[..start].ends_with('@') {
+            self.warnings.push(LexWarning {
+                code: WarningCode::DoubledSilence,
+                span: start..start + 2,
+            });
         }
     }
 }
+
+/// The position of a lexer and the context it tracks, see `Lexer::save`.
+#[cfg(feature = "async")]
+struct Checkpoint {
+    pos: usize,
+    location: Location,
+    index: usize,
+    warnings: usize,
+    stats: Option<LexStats>,
+    recent: [Option<TokenType>; 2],
+    parens: Vec<bool>,
+    brackets: Vec<bool>,
+    braces: Vec<bool>,
+    html: bool,
+    last_comma: usize,
+    depth: usize,
+}
+
+/// Lexing a script that arrives in chunks, see `stream::TokenStream`.
+#[cfg(feature = "async")]
+impl Lexer<'_> {
+    /// Appends text to the end of the script, and to the range that is lexed.
+    fn push_str(&mut self, text: &str) {
+        self.script.to_mut().push_str(text);
+        self.range.end = self.script.len();
+    }
+
+    /// Saves the position of the lexer, so a token lexed from an incomplete script can be undone.
+    fn save(&self) -> Checkpoint {
+        Checkpoint {
+            pos: self.pos,
+            location: self.location,
+            index: self.index,
+            warnings: self.warnings.len(),
+            stats: self.stats.clone(),
+            recent: self.recent.clone(),
+            parens: self.parens.clone(),
+            brackets: self.brackets.clone(),
+            braces: self.braces.clone(),
+            html: self.html,
+            last_comma: self.last_comma,
+            depth: self.depth,
+        }
+    }
+
+    fn restore(&mut self, checkpoint: Checkpoint) {
+        self.pos = checkpoint.pos;
+        self.location = checkpoint.location;
+        self.index = checkpoint.index;
+        self.warnings.truncate(checkpoint.warnings);
+        self.stats = checkpoint.stats;
+        self.recent = checkpoint.recent;
+        self.parens = checkpoint.parens;
+        self.brackets = checkpoint.brackets;
+        self.braces = checkpoint.braces;
+        self.html = checkpoint.html;
+        self.last_comma = checkpoint.last_comma;
+        self.depth = checkpoint.depth;
+    }
+}
+
+/// The line and column of the byte `offset` within the script.
+fn location_at(script: &str, offset: usize) -> Location {
+    let before = &script[..offset];
+    match before.rfind('\n') {
+        Some(lf) => Location::new(
+            before.matches('\n').count() + 1,
+            before[lf + 1..].chars().count() + 1,
+        ),
+        None => Location::new(1, before.chars().count() + 1),
+    }
+}
+
+/// An incomplete sequence at the end of the input spans until the end of the input.
+fn invalid_utf8(error: std::str::Utf8Error, len: usize) -> Error {
+    let start = error.valid_up_to();
+    let end = error.error_len().map_or(len, |invalid| start + invalid);
+    LexError::InvalidUtf8 { span: start..end }.into()
+}
+
+/// Whether the character may be used within an identifier, after its first character.
+fn is_identifier_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// The value of digits written in the given radix, falling back to a float when they overflow.
+fn radix_value(digits: &str, radix: u32) -> Numeric {
+    if let Ok(i) = i32::from_str_radix(digits, radix) {
+        Numeric::Int(i)
+    } else if let Ok(i) = i128::from_str_radix(digits, radix) {
+        Numeric::LInt(i)
+    } else {
+        Numeric::Float(digits.chars().fold(0.0, |value, c| {
+            value * radix as f64 + c.to_digit(radix).unwrap_or_default() as f64
+        }))
+    }
+}
+
+/// Suggests a fix for a character that does not begin any token.
+fn unexpected_char_hint(character: char, pos: usize) -> Option<&'static str> {
+    match character {
+        '“' | '”' | '„' => Some("did you mean to use a double quote `\"`?"),
+        '‘' | '’' => Some("did you mean to use a single quote `'`?"),
+        '`' => Some("shell execution using backticks is not supported"),
+        _ if pos == 0 => Some("did you mean to open a PHP tag with `<?php`?"),
+        _ => None,
+    }
+}
+
+/// The length of the inline HTML at the start of `rest`, up to the next open tag.
+fn html_len(rest: &str, legacy_tags: bool) -> usize {
+    rest.match_indices('<')
+        .map(|(i, _)| i)
+        .find(|&i| {
+            let tag = &rest[i..];
+            tag.starts_with("<?")
+                || (legacy_tags && (tag.starts_with("<%") || script_tag_len(tag).is_some()))
+        })
+        .unwrap_or(rest.len())
+}
+
+/// Gets the length of a `</script>` tag at the start of `rest`, which may have whitespace
+/// before its `>`.
+fn script_close_tag_len(rest: &str) -> Option<usize> {
+    let head = rest.get(..8)?;
+    if !head.eq_ignore_ascii_case("</script") {
+        return None;
+    }
+    let spaces = rest[8..].len() - rest[8..].trim_start().len();
+    rest[8 + spaces..].starts_with('>').then_some(9 + spaces)
+}
+
+/// Gets the length of a `<script language="php">` tag at the start of `rest`.
+fn script_tag_len(rest: &str) -> Option<usize> {
+    let head = rest.get(..7)?;
+    if !head.eq_ignore_ascii_case("<script") {
+        return None;
+    }
+
+    let len = rest.find('>')? + 1;
+    let tag = rest[..len]
+        .to_ascii_lowercase()
+        .replace(['"', '\'', ' '], "");
+    if tag.contains("language=php") {
+        Some(len)
+    } else {
+        None
+    }
+}
+
+/// Whether the body of a brace begins with a property hook, eg: ` final &get => 1; }`.
+fn is_hooks(body: &str) -> bool {
+    let body = body.trim_start();
+    let body = match body.get(..5) {
+        Some(word) if word.eq_ignore_ascii_case("final") => body[5..].trim_start(),
+        _ => body,
+    };
+    let body = body.strip_prefix('&').unwrap_or(body).trim_start();
+    body.get(..3).is_some_and(|word| {
+        (word.eq_ignore_ascii_case("get") || word.eq_ignore_ascii_case("set"))
+            && !body[3..].starts_with(|c: char| c.is_alphanumeric() || c == '_')
+    })
+}
+
+/// Gets the length of a curly brace string offset, eg: `$str{0}` at the start of `rest`.
+/// The hooks of a property written without a space are not an offset, eg: `$x{ get; }`.
+fn curly_offset_len(rest: &str) -> Option<usize> {
+    let name = rest.strip_prefix('$')?;
+    let name_len = name
+        .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .unwrap_or(name.len());
+    if name_len == 0 || !name[name_len..].starts_with('{') || is_hooks(&name[name_len + 1..]) {
+        return None;
+    }
+
+    let mut depth = 0;
+    for (i, c) in name[name_len..].char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(1 + name_len + i + 1);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // unterminated, only report up to the brace.
+    Some(1 + name_len + 1)
+}
@@ -0,0 +1,49 @@
+use std::ops::Range;
+
+/// A byte range of a script, eg: the range a token or an AST node spans.
+/// Unlike `Range` this is `Copy`, so it can be passed around freely while building nodes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize, bincode::Encode, bincode::Decode)
+)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    pub fn new(start: usize, end: usize) -> Self {
+        Self { start, end }
+    }
+
+    /// The span covering both spans, including anything between them.
+    pub fn merge(a: Span, b: Span) -> Span {
+        Span::new(a.start.min(b.start), a.end.max(b.end))
+    }
+
+    /// The span covering every span, `None` if there are no spans.
+    pub fn cover(spans: &[Span]) -> Option<Span> {
+        spans.iter().copied().reduce(Span::merge)
+    }
+
+    pub fn len(&self) -> usize {
+        self.end.saturating_sub(self.start)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl From<Range<usize>> for Span {
+    fn from(range: Range<usize>) -> Self {
+        Span::new(range.start, range.end)
+    }
+}
+
+impl From<Span> for Range<usize> {
+    fn from(span: Span) -> Self {
+        span.start..span.end
+    }
+}
@@ -0,0 +1,144 @@
+use std::{
+    io::Error,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures_core::Stream;
+
+use super::{error::LexError, token::Token, Lexer};
+
+/// How close to the end of the received input a token may end before it is yielded.
+/// A token ending any closer may still grow once more input arrives, eg: `<?` may be the start
+/// of `<?php`, so it is lexed again with the next chunk.
+const LOOKAHEAD: usize = 16;
+
+/// An adapter lexing a script as it arrives from an asynchronous stream of byte chunks,
+/// eg: a file read with tokio. Lexing itself stays synchronous, tokens are yielded as soon as
+/// enough of the script has arrived to know where they end.
+///
+/// The stream ends after the first error, which may be an error of the source stream.
+pub struct TokenStream<S> {
+    source: S,
+    lexer: Lexer<'static>,
+    /// Bytes received that do not form a complete UTF-8 sequence yet.
+    pending: Vec<u8>,
+    /// Whether the source stream has ended.
+    finished: bool,
+    /// Whether an error was yielded, after which the stream ends.
+    failed: bool,
+}
+
+impl<S, B> TokenStream<S>
+where
+    S: Stream<Item = Result<B, Error>> + Unpin,
+    B: AsRef<[u8]>,
+{
+    pub fn new(source: S) -> Self {
+        Self::with_lexer(source, Lexer::new(""))
+    }
+
+    /// Lexes the chunks with the given lexer, so any of its options are used.
+    /// The script of the lexer is lexed before any chunk, this is usually empty.
+    pub fn with_lexer(source: S, lexer: Lexer<'static>) -> Self {
+        Self {
+            source,
+            lexer,
+            pending: Vec::new(),
+            finished: false,
+            failed: false,
+        }
+    }
+
+    /// The lexer tokens are lexed with, eg: to retrieve its warnings.
+    pub fn lexer(&self) -> &Lexer<'static> {
+        &self.lexer
+    }
+
+    /// Lexes the next token, `None` if more input is needed before it is known where it ends.
+    fn lex_next(&mut self) -> Option<Result<Option<Token>, Error>> {
+        let checkpoint = self.lexer.save();
+        let result = self.lexer.next();
+        if self.finished {
+            return Some(result);
+        }
+
+        let complete = self.lexer.range.end;
+        let end = match &result {
+            Ok(Some(token)) => Some(token.1.end),
+            Ok(None) => None,
+            Err(error) => LexError::from_io(error).map(|error| error.span().end),
+        };
+        match end {
+            Some(end) if end + LOOKAHEAD < complete => Some(result),
+            _ => {
+                self.lexer.restore(checkpoint);
+                None
+            }
+        }
+    }
+
+    /// Appends a chunk to the script, holding back any incomplete UTF-8 sequence it ends with.
+    fn receive(&mut self, chunk: &[u8]) -> Result<(), Error> {
+        self.pending.extend_from_slice(chunk);
+        let valid = match std::str::from_utf8(&self.pending) {
+            Ok(text) => text.len(),
+            Err(error) if error.error_len().is_none() => error.valid_up_to(),
+            Err(error) => {
+                let start = self.lexer.range.end + error.valid_up_to();
+                let end = start + error.error_len().unwrap_or_default();
+                return Err(LexError::InvalidUtf8 { span: start..end }.into());
+            }
+        };
+
+        let text = std::str::from_utf8(&self.pending[..valid]).unwrap_or_default();
+        self.lexer.push_str(text);
+        self.pending.drain(..valid);
+        Ok(())
+    }
+}
+
+impl<S, B> Stream for TokenStream<S>
+where
+    S: Stream<Item = Result<B, Error>> + Unpin,
+    B: AsRef<[u8]>,
+{
+    type Item = Result<Token, Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        loop {
+            if this.failed {
+                return Poll::Ready(None);
+            }
+
+            if let Some(result) = this.lex_next() {
+                this.failed = result.is_err();
+                return Poll::Ready(result.transpose());
+            }
+
+            match Pin::new(&mut this.source).poll_next(cx) {
+                Poll::Pending => return Poll::Pending,
+                Poll::Ready(Some(Ok(chunk))) => {
+                    if let Err(error) = this.receive(chunk.as_ref()) {
+                        this.failed = true;
+                        return Poll::Ready(Some(Err(error)));
+                    }
+                }
+                Poll::Ready(Some(Err(error))) => {
+                    this.failed = true;
+                    return Poll::Ready(Some(Err(error)));
+                }
+                Poll::Ready(None) => {
+                    this.finished = true;
+                    if !this.pending.is_empty() {
+                        this.failed = true;
+                        let start = this.lexer.range.end;
+                        let span = start..start + this.pending.len();
+                        return Poll::Ready(Some(Err(LexError::InvalidUtf8 { span }.into())));
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,54 @@
+use super::token::{Token, TokenType};
+
+/// Asserts that the tokens match the expected kinds and texts, panicking with a readable
+/// description of the first difference otherwise.
+///
+/// ```rust
+/// use compiler::syntax::lex::{test_utils::assert_tokens_eq, token::TokenType, Lexer};
+///
+/// let tokens = Lexer::new("foo;").tokenize().unwrap();
+/// assert_tokens_eq(&tokens, &[(TokenType::Identifier, "foo"), (TokenType::EOS, ";")]);
+/// ```
+#[track_caller]
+pub fn assert_tokens_eq(actual: &[Token], expected: &[(TokenType, &str)]) {
+    if let Some(diff) = tokens_diff(actual, expected) {
+        panic!("{}", diff);
+    }
+}
+
+/// Describes the first difference between the tokens and the expected kinds and texts,
+/// or `None` if they match.
+pub fn tokens_diff(actual: &[Token], expected: &[(TokenType, &str)]) -> Option<String> {
+    for (i, (token, (kind, text))) in actual.iter().zip(expected).enumerate() {
+        let got = token.text().unwrap_or_default();
+        if token.0 != *kind || got != *text {
+            return Some(format!(
+                "tokens differ at index {}:\n  expected: {:?} {:?}\n       got: {:?} {:?} at {:?}",
+                i, kind, text, token.0, got, token.1
+            ));
+        }
+    }
+
+    if actual.len() != expected.len() {
+        let extra = match actual.get(expected.len()) {
+            Some(token) => format!(
+                "first unexpected token: {:?} {:?} at {:?}",
+                token.0,
+                token.text().unwrap_or_default(),
+                token.1
+            ),
+            None => {
+                let (kind, text) = &expected[actual.len()];
+                format!("first missing token: {:?} {:?}", kind, text)
+            }
+        };
+        return Some(format!(
+            "expected {} tokens, got {}, {}",
+            expected.len(),
+            actual.len(),
+            extra
+        ));
+    }
+
+    None
+}
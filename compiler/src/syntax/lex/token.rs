@@ -1,12 +1,53 @@
-use std::ops::Range;
+use std::{borrow::Cow, ops::Range};
 
 use crate::syntax::ast::{
     keyword::Keyword,
+    operator::OperatorKind,
     reserved::{ReservedCall, ReservedIdent},
 };
 
-#[derive(Debug, Clone)]
-pub struct Token(pub TokenType, pub Range<usize>, pub Option<String>);
+use super::span::Span;
+
+/// A token lexed from a script, holding its type, the byte range it spans, its value,
+/// its index within the tokens lexed by the `Lexer` and the lines and columns it spans.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize, bincode::Encode, bincode::Decode)
+)]
+pub struct Token(
+    pub TokenType,
+    pub Range<usize>,
+    pub Option<String>,
+    pub usize,
+    pub Range<Location>,
+);
+
+/// A line and column within a script, both starting at 1.
+/// Columns are counted in chars, rather than bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize, bincode::Encode, bincode::Decode)
+)]
+pub struct Location {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Location {
+    pub const START: Location = Location { line: 1, column: 1 };
+
+    pub fn new(line: usize, column: usize) -> Self {
+        Self { line, column }
+    }
+}
+
+impl Default for Location {
+    fn default() -> Self {
+        Location::START
+    }
+}
 
 impl Token {
     pub fn kind(&self) -> TokenType {
@@ -20,29 +61,198 @@ impl Token {
     pub fn range(&self) -> Range<usize> {
         self.1.clone()
     }
+
+    /// The byte range of this token as a `Span`.
+    pub fn span(&self) -> Span {
+        self.1.clone().into()
+    }
+
+    /// The text of the script this token was lexed from, as it was written.
+    /// Unlike `text` this is never decoded or normalized, eg: escapes are kept as is.
+    pub fn source<'s>(&self, script: &'s str) -> &'s str {
+        &script[self.1.clone()]
+    }
+
+    /// The position of this token within the tokens lexed, starting at zero.
+    pub fn index(&self) -> usize {
+        self.3
+    }
+
+    /// The line and column this token starts at, until the line and column it ends at.
+    pub fn location(&self) -> Range<Location> {
+        self.4.clone()
+    }
+
+    /// Gets the text of the token, regardless of where it is stored.
+    /// Tokens with a value yield that value, while tokens such as keywords or
+    /// brackets yield their canonical text, eg: `{` for `LeftBrace`.
+    pub fn text(&self) -> Option<Cow<'_, str>> {
+        if let Some(value) = &self.2 {
+            return Some(Cow::Borrowed(value));
+        }
+
+        let text = match &self.0 {
+            TokenType::EOF | TokenType::Constant => return None,
+            TokenType::Keyword(keyword) => keyword.as_str(),
+            TokenType::ReservedCall(call) => call.as_str(),
+            TokenType::ReservedIdent(ident) => ident.as_str(),
+            TokenType::NumericalLit(n) => {
+                return Some(Cow::Owned(match n {
+                    Numeric::Float(f) => f.to_string(),
+                    Numeric::Int(i) => i.to_string(),
+                    Numeric::LInt(i) => i.to_string(),
+                }))
+            }
+            TokenType::Accessor(AccessType::StaticMember) => "::",
+            TokenType::Accessor(AccessType::ReferenceMember) => "->",
+            TokenType::Accessor(AccessType::NullsafeMember) => "?->",
+            TokenType::BOM => "\u{feff}",
+            TokenType::EOS => ";",
+            TokenType::LF(LF::CRLF) => "\r\n",
+            TokenType::LF(LF::LF) => "\n",
+            TokenType::LeftBracket => "[",
+            TokenType::RightBracket => "]",
+            TokenType::AttributeStart => "#[",
+            TokenType::AttributeEnd => "]",
+            TokenType::LeftParenthesis => "(",
+            TokenType::RightParenthesis => ")",
+            TokenType::LeftBrace => "{",
+            TokenType::RightBrace => "}",
+            TokenType::Comma => ",",
+            TokenType::Colon => ":",
+            TokenType::Dot => ".",
+            TokenType::Ellipsis => "...",
+            TokenType::Backslash => "\\",
+            TokenType::QuestionMark => "?",
+            TokenType::Variable => "$",
+            // these always carry their value.
+            TokenType::Comment
+            | TokenType::Shebang
+            | TokenType::OpenTag
+            | TokenType::CloseTag
+            | TokenType::InlineHtml
+            | TokenType::Identifier
+            | TokenType::StringLit(_)
+            | TokenType::Operator
+            | TokenType::Boolean
+            | TokenType::Null
+            | TokenType::Whitespace => return None,
+        };
+
+        Some(Cow::Borrowed(text))
+    }
+
+    /// Whether this is a contextual keyword, which may still be used as a name, eg:
+    /// `function match() {}` before PHP 8.0, or a `from` that does not follow `yield`.
+    /// These are always lexed as keywords, the parser may demote them to identifiers.
+    /// See `Keyword::is_contextual`.
+    pub fn could_be_identifier(&self) -> bool {
+        matches!(self.0, TokenType::Keyword(keyword) if keyword.is_contextual())
+    }
+
+    /// The amount of blank lines within a run of line breaks, lexed with
+    /// `Lexer::split_whitespace`. This is zero for any other token.
+    pub fn blank_lines(&self) -> usize {
+        match (&self.0, &self.2) {
+            (TokenType::LF(_), Some(breaks)) => breaks.matches('\n').count().saturating_sub(1),
+            _ => 0,
+        }
+    }
+
+    /// Whether this token ends an operand, so an operator following it is binary, eg: the `-`
+    /// of `$a -5` subtracts, while the `-` of `= -5` negates. This is true for identifiers,
+    /// literals and closing brackets or parenthesis. A variable ends with its name, which is
+    /// an identifier, so the `$` before it does not end an operand.
+    pub fn ends_operand(&self) -> bool {
+        self.0.ends_operand()
+    }
+
+    /// The kind of operator this token is, `None` if the token is not an operator.
+    pub fn operator_kind(&self) -> Option<OperatorKind> {
+        match self.0 {
+            TokenType::Operator => self.2.as_deref()?.parse().ok(),
+            _ => None,
+        }
+    }
+}
+
+/// Finds the token covering the byte `offset`, the tokens must be in the order they were lexed.
+/// As token ranges are exclusive, an offset at the end of a token belongs to the token after it.
+/// Returns `None` if no token covers the offset, eg: when the whitespace around it was dropped.
+pub fn token_at_offset(tokens: &[Token], offset: usize) -> Option<&Token> {
+    let after = tokens.partition_point(|t| t.1.start <= offset);
+    let token = tokens.get(after.checked_sub(1)?)?;
+    token.1.contains(&offset).then_some(token)
+}
+
+/// The name of a variable, as given by `variable_name`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VariableName<'t> {
+    /// A variable named by an identifier, holding the name without its `$`, eg: `foo` for `$foo`.
+    Named(&'t str),
+    /// A variable variable, named by the value of an expression, eg: `$$x` or `${'x'}`.
+    Dynamic,
+}
+
+/// The name of the variable whose `$` is the token at `index`, `None` if that token is not a `$`.
+/// As the `$` and the name are lexed as separate tokens, the name is the token directly following
+/// it, so the tokens must be in the order they were lexed.
+pub fn variable_name(tokens: &[Token], index: usize) -> Option<VariableName<'_>> {
+    let dollar = tokens.get(index)?;
+    if dollar.0 != TokenType::Variable {
+        return None;
+    }
+    let name = tokens.get(index + 1).filter(|t| t.1.start == dollar.1.end);
+    match name {
+        Some(Token(TokenType::Identifier, _, Some(name), ..)) => Some(VariableName::Named(name)),
+        Some(Token(TokenType::Variable | TokenType::LeftBrace, ..)) => Some(VariableName::Dynamic),
+        _ => None,
+    }
 }
 
-/// A simple utility macro to create a token from an expression, for example:
+/// A simple utility macro to create a token from an expression.
+/// The index and location of the token are left at their defaults, as they are assigned
+/// by the `Lexer`. For example:
 ///
 /// ```rust no_run
-/// use crate::syntax::lex::token::token;
+/// use compiler::syntax::lex::token::{Token, TokenType, LF};
+/// use compiler::token;
 ///
-/// let lf_tk: Token = token!(1, 2, TokenType::LF, Some(LF::CRLF));
+/// let lf_tk: Result<Option<Token>, std::io::Error> =
+///     token!(1, 2, TokenType::LF(LF::CRLF), Some("\r\n".to_string()));
 /// ```
 #[macro_export]
 macro_rules! token {
     ($start: expr, $end: expr, $t: expr, $v: expr) => {
-        Ok(Some(Token($t, $start..$end, $v)))
+        Ok(Some($crate::syntax::lex::token::Token(
+            $t,
+            $start..$end,
+            $v,
+            0,
+            $crate::syntax::lex::token::Location::START
+                ..$crate::syntax::lex::token::Location::START,
+        )))
     };
     ($start: expr, $end: expr, $t: expr) => {
-        Ok(Some(Token($t, $start..$end, None)))
+        Ok(Some($crate::syntax::lex::token::Token(
+            $t,
+            $start..$end,
+            None,
+            0,
+            $crate::syntax::lex::token::Location::START
+                ..$crate::syntax::lex::token::Location::START,
+        )))
     };
 }
 
 /// A enum representing a valid php numerical.
 /// ! WARNING, THIS COMPILER DIFFERS IN NUMERICAL IMPLMENTATION FROM PHP
 /// ! IN PHP INTS ARE TREATED AS FLOATS, THIS COMPILER RESPECTS THE DATA TYPE
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize, bincode::Encode, bincode::Decode)
+)]
 pub enum Numeric {
     Float(f64),
     Int(i32),
@@ -52,7 +262,37 @@ pub enum Numeric {
     LInt(i128),
 }
 
-#[derive(Debug, Clone)]
+impl Numeric {
+    /// The value as a float, which is lossy for a `LInt` beyond 2^53.
+    pub fn as_f64(&self) -> f64 {
+        match self {
+            Numeric::Float(f) => *f,
+            Numeric::Int(i) => *i as f64,
+            Numeric::LInt(i) => *i as f64,
+        }
+    }
+
+    /// The value as an integer, a float is truncated towards zero like PHP's `(int)` cast,
+    /// eg: `-1.9` is `-1`. `None` if the value does not fit, eg: a `LInt` beyond `i64`
+    /// or a float that is out of range, infinite or NaN.
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            Numeric::Int(i) => Some(*i as i64),
+            Numeric::LInt(i) => i64::try_from(*i).ok(),
+            // `i64::MAX as f64` rounds up to 2^63, which is out of range.
+            Numeric::Float(f) if f.is_finite() && *f >= i64::MIN as f64 && *f < i64::MAX as f64 => {
+                Some(f.trunc() as i64)
+            }
+            Numeric::Float(_) => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize, bincode::Encode, bincode::Decode)
+)]
 pub enum AccessType {
     /// Another term used to refer to this access is "Scoped Resolution".
     /// However for simplicity, the token is named "StaticMember". This is however,
@@ -79,15 +319,32 @@ pub enum AccessType {
     /// //       ~~ Referenced Member
     /// ```
     ReferenceMember,
+    /// Similar to a "ReferenceMember" access, however evaluates to `null` rather than
+    /// throwing when the left hand side is `null`.
+    ///
+    /// EG:
+    /// ```php
+    /// $instance?->member;
+    /// //       ~~~ Nullsafe Member
+    /// ```
+    NullsafeMember,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize, bincode::Encode, bincode::Decode)
+)]
 pub enum LF {
     CRLF,
     LF,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize, bincode::Encode, bincode::Decode)
+)]
 pub enum StringType {
     /// A single qoute string. Allows use for multi-lined strings.
     Single,
@@ -131,13 +388,31 @@ pub enum StringType {
 ///
 /// Other deviations of this lexical structure include:
 /// - visibility is PARSED not lexed, during lexing, visibility is a "keyword"
-/// - default functions, like echo, and print are not
+/// - language constructs called like functions, like eval and isset, are not
 ///   represented by their own token, but rather a value wrapped within a
-///   `ReservedCall`
-#[derive(Debug, Clone)]
+///   `ReservedCall`, while `print` is a `Keyword` and `echo` an identifier
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize, bincode::Encode, bincode::Decode)
+)]
 pub enum TokenType {
     /// End of File
     EOF,
+    /// The UTF-8 byte order mark, which may only appear at the very start of a script.
+    /// PHP outputs this as is, which is a common source of "headers already sent" bugs.
+    BOM,
+    /// The tag opening a block of PHP code, either `<?php`, `<?=` or the short `<?`.
+    OpenTag,
+    /// The `?>` tag closing a block of PHP code.
+    /// Outside of `HtmlMode::Code` this includes the line break directly following it,
+    /// as PHP does not output it.
+    CloseTag,
+    /// Text outside of PHP tags, see `Lexer::html_mode`.
+    InlineHtml,
+    /// A `#!` interpreter line, such as `#!/usr/bin/php`, only valid as the first line of a script.
+    /// The value holds the line, without the line break.
+    Shebang,
     /// Used to identify any form of comments.
     /// This meta is saved.
     Comment,
@@ -150,9 +425,10 @@ pub enum TokenType {
     Keyword(Keyword),
 
     /// A function that is implemented within the language itself, such as:
-    /// - `print`
-    /// or
     /// - `eval`
+    ///
+    /// or
+    /// - `isset`
     ReservedCall(ReservedCall),
 
     /// A identifier that is reserved by PHP, for example, a magic constant,
@@ -191,6 +467,9 @@ pub enum TokenType {
     /// A token that represents a boolean value. Either "true" or "false".
     Boolean,
 
+    /// The `null` literal, which is case insensitive like booleans.
+    Null,
+
     /// A character that is not a letter, number or a string and is not visible
     Whitespace,
 
@@ -207,6 +486,12 @@ pub enum TokenType {
     /// The `]` character that signals the end of a return type, an array, etc.
     RightBracket,
 
+    /// The `#[` that starts an attribute group, eg: `#[Route('/')]`. Since PHP 8.0.
+    AttributeStart,
+
+    /// The `]` that ends an attribute group, as opposed to one closing a bracket within it.
+    AttributeEnd,
+
     /// The `(` character that signals the start of a argument or expression condition.
     LeftParenthesis,
 
@@ -220,6 +505,8 @@ pub enum TokenType {
     RightBrace,
 
     /// The `,` character that signals the end of a parameter.
+    /// Each comma is its own token, even when nothing is between them, so a skipped element
+    /// of a destructuring is a comma directly following `[`, `(` or another comma, eg: `[, $b]`.
     Comma,
 
     /// The `:` character that signals a type? this probably isnt allowed in php
@@ -228,6 +515,10 @@ pub enum TokenType {
     /// The `.` character that signals the concation of a string.
     Dot,
 
+    /// The `...` characters, spreading an array into arguments or declaring a variadic
+    /// parameter, eg: `function f(int ...$nums)`.
+    Ellipsis,
+
     /// The `\` character that signals the start of a string literal.
     Backslash,
 
@@ -238,3 +529,111 @@ pub enum TokenType {
     /// > - < wtf php!
     Variable,
 }
+
+/// The category of a `TokenType`, without the data some variants carry.
+/// Unlike `TokenType` this may be hashed, eg: to count tokens by their category.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TokenKind {
+    EOF,
+    BOM,
+    OpenTag,
+    CloseTag,
+    InlineHtml,
+    Shebang,
+    Comment,
+    Constant,
+    Keyword,
+    ReservedCall,
+    ReservedIdent,
+    Identifier,
+    NumericalLit,
+    StringLit,
+    Operator,
+    Accessor,
+    Boolean,
+    Null,
+    Whitespace,
+    EOS,
+    LF,
+    LeftBracket,
+    RightBracket,
+    AttributeStart,
+    AttributeEnd,
+    LeftParenthesis,
+    RightParenthesis,
+    LeftBrace,
+    RightBrace,
+    Comma,
+    Colon,
+    Dot,
+    Ellipsis,
+    Backslash,
+    QuestionMark,
+    Variable,
+}
+
+impl TokenType {
+    /// The category of this token type, eg: both `Keyword(If)` and `Keyword(Else)` are
+    /// `TokenKind::Keyword`.
+    pub fn kind(&self) -> TokenKind {
+        TokenKind::from(self)
+    }
+
+    /// Whether a token of this type ends an operand, see `Token::ends_operand`.
+    pub fn ends_operand(&self) -> bool {
+        matches!(
+            self,
+            TokenType::Identifier
+                | TokenType::ReservedIdent(_)
+                | TokenType::NumericalLit(_)
+                | TokenType::StringLit(_)
+                | TokenType::Boolean
+                | TokenType::Null
+                | TokenType::RightParenthesis
+                | TokenType::RightBracket
+        )
+    }
+}
+
+impl From<&TokenType> for TokenKind {
+    fn from(token_type: &TokenType) -> Self {
+        match token_type {
+            TokenType::EOF => TokenKind::EOF,
+            TokenType::BOM => TokenKind::BOM,
+            TokenType::OpenTag => TokenKind::OpenTag,
+            TokenType::CloseTag => TokenKind::CloseTag,
+            TokenType::InlineHtml => TokenKind::InlineHtml,
+            TokenType::Shebang => TokenKind::Shebang,
+            TokenType::Comment => TokenKind::Comment,
+            TokenType::Constant => TokenKind::Constant,
+            TokenType::Keyword(_) => TokenKind::Keyword,
+            TokenType::ReservedCall(_) => TokenKind::ReservedCall,
+            TokenType::ReservedIdent(_) => TokenKind::ReservedIdent,
+            TokenType::Identifier => TokenKind::Identifier,
+            TokenType::NumericalLit(_) => TokenKind::NumericalLit,
+            TokenType::StringLit(_) => TokenKind::StringLit,
+            TokenType::Operator => TokenKind::Operator,
+            TokenType::Accessor(_) => TokenKind::Accessor,
+            TokenType::Boolean => TokenKind::Boolean,
+            TokenType::Null => TokenKind::Null,
+            TokenType::Whitespace => TokenKind::Whitespace,
+            TokenType::EOS => TokenKind::EOS,
+            TokenType::LF(_) => TokenKind::LF,
+            TokenType::LeftBracket => TokenKind::LeftBracket,
+            TokenType::RightBracket => TokenKind::RightBracket,
+            TokenType::AttributeStart => TokenKind::AttributeStart,
+            TokenType::AttributeEnd => TokenKind::AttributeEnd,
+            TokenType::LeftParenthesis => TokenKind::LeftParenthesis,
+            TokenType::RightParenthesis => TokenKind::RightParenthesis,
+            TokenType::LeftBrace => TokenKind::LeftBrace,
+            TokenType::RightBrace => TokenKind::RightBrace,
+            TokenType::Comma => TokenKind::Comma,
+            TokenType::Colon => TokenKind::Colon,
+            TokenType::Dot => TokenKind::Dot,
+            TokenType::Ellipsis => TokenKind::Ellipsis,
+            TokenType::Backslash => TokenKind::Backslash,
+            TokenType::QuestionMark => TokenKind::QuestionMark,
+            TokenType::Variable => TokenKind::Variable,
+        }
+    }
+}
@@ -0,0 +1,42 @@
+use std::fmt;
+
+/// A version of PHP that scripts are lexed against.
+/// Syntax introduced or removed in later versions is reported through warnings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize, bincode::Encode, bincode::Decode)
+)]
+pub struct PhpVersion {
+    pub major: u8,
+    pub minor: u8,
+}
+
+impl PhpVersion {
+    pub const PHP_7_3: PhpVersion = PhpVersion::new(7, 3);
+    pub const PHP_7_4: PhpVersion = PhpVersion::new(7, 4);
+    pub const PHP_8_0: PhpVersion = PhpVersion::new(8, 0);
+    pub const PHP_8_1: PhpVersion = PhpVersion::new(8, 1);
+    pub const PHP_8_2: PhpVersion = PhpVersion::new(8, 2);
+    pub const PHP_8_3: PhpVersion = PhpVersion::new(8, 3);
+    pub const PHP_8_4: PhpVersion = PhpVersion::new(8, 4);
+
+    /// The latest version supported, this is the default target of the lexer.
+    pub const LATEST: PhpVersion = PhpVersion::PHP_8_4;
+
+    pub const fn new(major: u8, minor: u8) -> Self {
+        Self { major, minor }
+    }
+}
+
+impl Default for PhpVersion {
+    fn default() -> Self {
+        PhpVersion::LATEST
+    }
+}
+
+impl fmt::Display for PhpVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}", self.major, self.minor)
+    }
+}
@@ -0,0 +1,49 @@
+use std::ops::Range;
+
+/// A non-fatal diagnostic that the lexer collects alongside the tokens it produces.
+/// Warnings never stop lexing, the construct they refer to is still tokenized.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize, bincode::Encode, bincode::Decode)
+)]
+pub struct LexWarning {
+    pub code: WarningCode,
+    /// The byte range of the script this warning refers to.
+    pub span: Range<usize>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize, bincode::Encode, bincode::Decode)
+)]
+pub enum WarningCode {
+    /// The ASP style tags `<%`, `<%=` and `%>`, removed in PHP 7.0.
+    AspTag,
+    /// The `<script language="php">` open tag, removed in PHP 7.0.
+    ScriptTag,
+    /// A string offset accessed with curly braces, eg: `$str{0}`.
+    /// Deprecated in PHP 7.4 in favor of `$str[0]` and removed in PHP 8.0, so this is only
+    /// reported when targeting PHP 8.0 or later.
+    CurlyStringOffset,
+    /// A trailing comma in a parameter list or closure `use` list, only allowed since PHP 8.0,
+    /// so this is only reported when targeting an earlier version.
+    TrailingComma,
+    /// A tab within whitespace, only reported with `Lexer::forbid_tabs`.
+    Tab,
+    /// Two silence operators, eg: `@@foo()`. This is valid, but most likely meant the attribute
+    /// syntax proposed for PHP 8.0 before `#[` was settled on. Only reported with
+    /// `Lexer::warn_typos`.
+    DoubledSilence,
+}
+
+impl WarningCode {
+    /// What was most likely meant, for warnings about a likely typo.
+    pub fn hint(self) -> Option<&'static str> {
+        match self {
+            WarningCode::DoubledSilence => Some("did you mean the attribute syntax `#[...]`?"),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,155 @@
+use compiler::syntax::{
+    ast::keyword::Keyword,
+    lex::{
+        analysis::{
+            extract_symbols, find_unbalanced, is_mixed_template, keyword_histogram,
+            token_histogram, DelimiterError, LexStats, SymbolKind,
+        },
+        span::Span,
+        token::TokenKind,
+        Lexer,
+    },
+};
+
+use super::{lex, TEST_SCRIPT};
+
+#[test]
+pub fn histogram_counts() {
+    let histogram = token_histogram(&lex(TEST_SCRIPT));
+
+    assert_eq!(histogram[&TokenKind::Variable], 6);
+    assert_eq!(histogram[&TokenKind::Keyword], 9);
+    assert_eq!(histogram[&TokenKind::StringLit], 2);
+    assert_eq!(histogram.get(&TokenKind::Whitespace), None);
+}
+
+#[test]
+pub fn keyword_counts() {
+    let histogram = keyword_histogram(&lex(TEST_SCRIPT));
+
+    assert_eq!(histogram[&Keyword::Function], 2);
+    assert_eq!(histogram[&Keyword::Public], 2);
+    assert_eq!(histogram[&Keyword::Private], 1);
+    assert_eq!(histogram[&Keyword::Class], 1);
+    assert_eq!(histogram[&Keyword::Use], 1);
+    assert_eq!(histogram[&Keyword::New], 1);
+    assert_eq!(histogram[&Keyword::Return], 1);
+    assert_eq!(histogram.get(&Keyword::Static), None);
+}
+
+#[test]
+pub fn pure_php_is_not_mixed() {
+    assert!(!is_mixed_template("<?php\necho 1;\n"));
+    assert!(!is_mixed_template("<?php echo 1; ?>\n"));
+    assert!(!is_mixed_template("#!/usr/bin/env php\n<?php echo 1;"));
+    assert!(!is_mixed_template(""));
+}
+
+#[test]
+pub fn mixed_template() {
+    assert!(is_mixed_template("<p><?php echo $title; ?></p>"));
+    assert!(is_mixed_template("<?php echo 1; ?>\n<p>footer</p>"));
+    assert!(is_mixed_template(" <?php echo 1;"));
+    assert!(is_mixed_template("<?= $a ?>, <?= $b ?>"));
+}
+
+#[test]
+pub fn no_tags_is_all_html() {
+    assert!(is_mixed_template("<html><body>Hello</body></html>"));
+}
+
+#[test]
+pub fn collected_stats() {
+    let script =
+        "// sums\nfunction total(array $values) {\n    return array_sum([$values['a'], \"b\"]);\n}";
+    let mut lexer = Lexer::new(script).collect_stats();
+    let tokens = lexer.tokenize().unwrap();
+
+    assert_eq!(
+        lexer.stats(),
+        Some(&LexStats {
+            tokens: tokens.len(),
+            max_depth: 4,
+            strings: 2,
+            comments: 1,
+            longest_identifier: 9,
+        })
+    );
+
+    lexer.reset();
+    assert_eq!(lexer.stats(), Some(&LexStats::default()));
+    assert_eq!(Lexer::new(script).stats(), None);
+}
+
+#[test]
+pub fn class_symbols() {
+    let script =
+        "class Point {\n    function distance($other) {\n        return sqrt($other->x);\n    }\n}";
+    let symbols: Vec<(String, SymbolKind, Span)> = extract_symbols(script)
+        .unwrap()
+        .into_iter()
+        .map(|s| (s.name, s.kind, s.span))
+        .collect();
+
+    assert_eq!(
+        symbols,
+        vec![
+            ("Point".to_string(), SymbolKind::Class, Span::new(6, 11)),
+            (
+                "distance".to_string(),
+                SymbolKind::Function,
+                Span::new(27, 35)
+            ),
+            ("other".to_string(), SymbolKind::Variable, Span::new(36, 42)),
+            (
+                "sqrt".to_string(),
+                SymbolKind::Identifier,
+                Span::new(61, 65)
+            ),
+            ("other".to_string(), SymbolKind::Variable, Span::new(66, 72)),
+            ("x".to_string(), SymbolKind::Identifier, Span::new(74, 75)),
+        ]
+    );
+    assert_eq!(&script[66..72], "$other");
+}
+
+fn unbalanced(script: &str) -> Vec<(Span, DelimiterError)> {
+    find_unbalanced(&Lexer::new(script).tokenize().unwrap())
+}
+
+#[test]
+pub fn balanced_delimiters() {
+    assert!(unbalanced("#[Attr] function f(array $a = [1]) { return $a[0]; }").is_empty());
+}
+
+#[test]
+pub fn missing_closer() {
+    assert_eq!(
+        unbalanced("if ($a) { foo($b;"),
+        vec![
+            (Span::new(8, 9), DelimiterError::Unclosed),
+            (Span::new(13, 14), DelimiterError::Unclosed),
+        ]
+    );
+}
+
+#[test]
+pub fn extra_closer() {
+    assert_eq!(
+        unbalanced("foo($a));"),
+        vec![(Span::new(7, 8), DelimiterError::Unopened)]
+    );
+}
+
+#[test]
+pub fn mismatched_pair() {
+    assert_eq!(
+        unbalanced("$a = [1, 2); $b = 3;"),
+        vec![(
+            Span::new(10, 11),
+            DelimiterError::Mismatched {
+                open: Span::new(5, 6)
+            }
+        )]
+    );
+}
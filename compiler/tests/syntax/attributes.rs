@@ -0,0 +1,57 @@
+use compiler::syntax::lex::{token::TokenType, version::PhpVersion, Lexer};
+
+use super::{kinds, lex};
+
+#[test]
+pub fn nested_array_attribute() {
+    let kinds = kinds("#[Attr([1, 2])] function f() {}");
+
+    assert_eq!(kinds[0], TokenType::AttributeStart);
+    assert_eq!(kinds[1], TokenType::Identifier);
+    assert_eq!(kinds[2], TokenType::LeftParenthesis);
+    assert_eq!(kinds[3], TokenType::LeftBracket);
+    assert_eq!(kinds[7], TokenType::RightBracket);
+    assert_eq!(kinds[8], TokenType::RightParenthesis);
+    assert_eq!(kinds[9], TokenType::AttributeEnd);
+    assert_eq!(
+        kinds
+            .iter()
+            .filter(|k| **k == TokenType::AttributeEnd)
+            .count(),
+        1
+    );
+}
+
+#[test]
+pub fn attribute_group() {
+    let tokens = lex("#[A, B([[]])]\n$x = [0];");
+    let ends: Vec<usize> = tokens
+        .iter()
+        .filter(|t| t.0 == TokenType::AttributeEnd)
+        .map(|t| t.1.start)
+        .collect();
+
+    assert_eq!(ends, vec![12]);
+    assert_eq!(tokens.last().unwrap().kind(), TokenType::EOS);
+    assert_eq!(tokens[tokens.len() - 2].kind(), TokenType::RightBracket);
+}
+
+#[test]
+pub fn hash_comment() {
+    let tokens = lex("# a comment\n$x;");
+
+    assert_eq!(tokens[0].kind(), TokenType::Comment);
+    assert_eq!(tokens[0].value().as_deref(), Some("# a comment"));
+    assert_eq!(tokens[1].kind(), TokenType::Variable);
+}
+
+#[test]
+pub fn attribute_is_comment_before_php_8() {
+    let tokens = Lexer::new("#[Attr]\n$x;")
+        .target(PhpVersion::PHP_7_4)
+        .tokenize()
+        .unwrap();
+
+    assert_eq!(tokens[0].kind(), TokenType::Comment);
+    assert_eq!(tokens[0].value().as_deref(), Some("#[Attr]"));
+}
@@ -0,0 +1,98 @@
+use compiler::syntax::lex::{
+    cache::{LexResult, TokenCache},
+    version::PhpVersion,
+    Lexer,
+};
+
+#[test]
+pub fn cache_hit() {
+    let mut cache = TokenCache::new(4);
+    let script = "$a = 1;";
+
+    let first = cache.tokenize(script).unwrap().to_vec();
+    let second = cache.tokenize(script).unwrap().to_vec();
+
+    assert_eq!(cache.lexes(), 1);
+    assert_eq!(first, second);
+    assert_eq!(first, Lexer::new(script).tokenize().unwrap());
+}
+
+#[test]
+pub fn cache_changed_source() {
+    let mut cache = TokenCache::new(4);
+
+    cache.tokenize("$a = 1;").unwrap();
+    let tokens = cache.tokenize("$a = 12;").unwrap().to_vec();
+
+    assert_eq!(cache.lexes(), 2);
+    assert_eq!(tokens, Lexer::new("$a = 12;").tokenize().unwrap());
+}
+
+#[test]
+pub fn cache_evicts_least_recently_used() {
+    let mut cache = TokenCache::new(2);
+
+    cache.tokenize("a").unwrap();
+    cache.tokenize("b").unwrap();
+    // `a` is now the most recently used, so `b` is evicted.
+    cache.tokenize("a").unwrap();
+    cache.tokenize("c").unwrap();
+    assert_eq!(cache.len(), 2);
+    assert_eq!(cache.lexes(), 3);
+
+    cache.tokenize("a").unwrap();
+    assert_eq!(cache.lexes(), 3);
+
+    cache.tokenize("b").unwrap();
+    assert_eq!(cache.lexes(), 4);
+}
+
+#[test]
+pub fn zero_capacity_holds_one_script() {
+    let mut cache = TokenCache::new(0);
+
+    cache.tokenize("a").unwrap();
+    cache.tokenize("a").unwrap();
+    assert_eq!(cache.len(), 1);
+    assert_eq!(cache.lexes(), 1);
+
+    cache.tokenize("b").unwrap();
+    assert_eq!(cache.len(), 1);
+    assert_eq!(cache.lexes(), 2);
+}
+
+#[test]
+pub fn lex_result_round_trip() {
+    let lexer = Lexer::new("<?php $a = $str{0};")
+        .target(PhpVersion::PHP_8_0)
+        .warn_deprecated()
+        .collect_stats();
+    let result = LexResult::new(lexer);
+    let bytes = result.to_bytes().unwrap();
+
+    assert_eq!(LexResult::from_bytes(&bytes).unwrap(), result);
+    assert_eq!(result.version, PhpVersion::PHP_8_0);
+    assert_eq!(
+        result.tokens,
+        Lexer::new("<?php $a = $str{0};").tokenize().unwrap()
+    );
+    assert_eq!(result.warnings.len(), 1);
+    assert_eq!(result.stats.unwrap().tokens, result.tokens.len());
+    assert_eq!(result.error, None);
+}
+
+#[test]
+pub fn lex_result_keeps_error() {
+    let result = LexResult::new(Lexer::new("$a = `ls`;"));
+    let cached = LexResult::from_bytes(&result.to_bytes().unwrap()).unwrap();
+
+    assert_eq!(cached.tokens.len(), 5);
+    assert!(cached.error.unwrap().contains("at 5 until 6"));
+}
+
+#[test]
+pub fn lex_result_invalid_bytes() {
+    let bytes = LexResult::new(Lexer::new("$a = 1;")).to_bytes().unwrap();
+
+    assert!(LexResult::from_bytes(&bytes[..bytes.len() / 2]).is_err());
+}
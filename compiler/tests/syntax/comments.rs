@@ -0,0 +1,93 @@
+use compiler::syntax::lex::{token::TokenType, CommentPolicy, Lexer};
+
+use super::lex;
+
+#[test]
+pub fn block_comment() {
+    let tokens = lex("/* a * b */ $x; /**/ /* unterminated");
+
+    assert_eq!(tokens[0].kind(), TokenType::Comment);
+    assert_eq!(tokens[0].value().as_deref(), Some("/* a * b */"));
+    assert_eq!(tokens[1].kind(), TokenType::Variable);
+    assert_eq!(tokens[4].value().as_deref(), Some("/**/"));
+    assert_eq!(tokens[5].value().as_deref(), Some("/* unterminated"));
+}
+
+#[test]
+pub fn block_comment_ends_at_first_terminator() {
+    let tokens = lex("/*/ $a */ $b; /* x */ */");
+
+    assert_eq!(tokens[0].value().as_deref(), Some("/*/ $a */"));
+    assert_eq!(tokens[1].kind(), TokenType::Variable);
+    assert_eq!(tokens[2].value().as_deref(), Some("b"));
+    assert_eq!(tokens[4].value().as_deref(), Some("/* x */"));
+    assert_eq!(tokens[5].kind(), TokenType::Operator);
+}
+
+#[test]
+pub fn unterminated_block_comment_ending_in_star() {
+    for script in ["$a; /* x *", "$a; /*", "$a; /*/"] {
+        let tokens = lex(script);
+        let comment = tokens.last().unwrap();
+
+        assert_eq!(comment.kind(), TokenType::Comment, "{}", script);
+        assert_eq!(comment.value().as_deref(), Some(&script[4..]), "{}", script);
+    }
+}
+
+#[test]
+pub fn block_comment_with_multibyte_chars() {
+    let tokens = lex("/* é ✓ */$a");
+
+    assert_eq!(tokens[0].value().as_deref(), Some("/* é ✓ */"));
+    assert_eq!(tokens[0].range(), 0..12);
+    assert_eq!(tokens[1].kind(), TokenType::Variable);
+    assert_eq!(tokens[1].range(), 12..13);
+}
+
+const COMMENTS: &str = "// line\n# hash\n/* block */\n/** doc */\n/**/$x;";
+
+fn comments(policy: CommentPolicy) -> Vec<String> {
+    Lexer::new(COMMENTS)
+        .comments(policy)
+        .tokenize()
+        .unwrap()
+        .into_iter()
+        .filter(|t| t.kind() == TokenType::Comment)
+        .filter_map(|t| t.value())
+        .collect()
+}
+
+#[test]
+pub fn retain_comments() {
+    assert_eq!(
+        comments(CommentPolicy::Retain),
+        vec!["// line", "# hash", "/* block */", "/** doc */", "/**/"]
+    );
+}
+
+#[test]
+pub fn drop_line_comments() {
+    assert_eq!(
+        comments(CommentPolicy::DropLine),
+        vec!["/* block */", "/** doc */", "/**/"]
+    );
+}
+
+#[test]
+pub fn drop_all_comments() {
+    let tokens = Lexer::new(COMMENTS)
+        .comments(CommentPolicy::DropAll)
+        .tokenize()
+        .unwrap();
+
+    assert!(comments(CommentPolicy::DropAll).is_empty());
+    // the remaining tokens are indexed without gaps.
+    assert!(tokens.iter().enumerate().all(|(i, t)| t.index() == i));
+    assert_eq!(tokens.last().unwrap().kind(), TokenType::EOS);
+}
+
+#[test]
+pub fn doc_comments_only() {
+    assert_eq!(comments(CommentPolicy::DocOnly), vec!["/** doc */"]);
+}
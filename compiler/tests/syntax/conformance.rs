@@ -0,0 +1,476 @@
+//! Compares our tokens against the token names PHP's own `token_get_all` gives for the same
+//! script, as `token_name` prints them for PHP 8.3. Whitespace is left out of both, as PHP
+//! folds the whitespace following `<?php` into its open tag.
+
+use compiler::syntax::{
+    ast::keyword::Keyword,
+    lex::{
+        token::{AccessType, Numeric, StringType, Token, TokenType},
+        HtmlMode, Lexer,
+    },
+};
+
+/// The PHP token of each operator, any operator not listed is a single char token named by
+/// the char itself, eg: `=`.
+const PHP_OPERATORS: &[(&str, &str)] = &[
+    ("**", "T_POW"),
+    ("<<", "T_SL"),
+    (">>", "T_SR"),
+    ("+=", "T_PLUS_EQUAL"),
+    ("-=", "T_MINUS_EQUAL"),
+    ("*=", "T_MUL_EQUAL"),
+    ("/=", "T_DIV_EQUAL"),
+    ("%=", "T_MOD_EQUAL"),
+    ("**=", "T_POW_EQUAL"),
+    (".=", "T_CONCAT_EQUAL"),
+    ("&=", "T_AND_EQUAL"),
+    ("|=", "T_OR_EQUAL"),
+    ("^=", "T_XOR_EQUAL"),
+    ("<<=", "T_SL_EQUAL"),
+    (">>=", "T_SR_EQUAL"),
+    ("??", "T_COALESCE"),
+    ("??=", "T_COALESCE_EQUAL"),
+    ("==", "T_IS_EQUAL"),
+    ("!=", "T_IS_NOT_EQUAL"),
+    ("<>", "T_IS_NOT_EQUAL"),
+    ("===", "T_IS_IDENTICAL"),
+    ("!==", "T_IS_NOT_IDENTICAL"),
+    ("<=", "T_IS_SMALLER_OR_EQUAL"),
+    (">=", "T_IS_GREATER_OR_EQUAL"),
+    ("<=>", "T_SPACESHIP"),
+    ("&&", "T_BOOLEAN_AND"),
+    ("||", "T_BOOLEAN_OR"),
+    ("=>", "T_DOUBLE_ARROW"),
+];
+
+/// The words we lex as identifiers that PHP lexes as their own token.
+const PHP_RESERVED_WORDS: &[(&str, &str)] = &[
+    ("echo", "T_ECHO"),
+    ("array", "T_ARRAY"),
+    ("list", "T_LIST"),
+    ("isset", "T_ISSET"),
+    ("unset", "T_UNSET"),
+    ("empty", "T_EMPTY"),
+    ("exit", "T_EXIT"),
+    ("die", "T_EXIT"),
+    ("eval", "T_EVAL"),
+];
+
+fn keyword_name(keyword: Keyword) -> String {
+    match keyword {
+        Keyword::And => "T_LOGICAL_AND".to_string(),
+        Keyword::Or => "T_LOGICAL_OR".to_string(),
+        Keyword::Xor => "T_LOGICAL_XOR".to_string(),
+        // not keywords to PHP, outside of `yield from`.
+        Keyword::Async | Keyword::Await | Keyword::From | Keyword::Get | Keyword::Set => {
+            "T_STRING".to_string()
+        }
+        keyword => format!("T_{}", keyword.as_str().to_uppercase()),
+    }
+}
+
+/// Maps our tokens to the names of the tokens PHP lexes for the same text.
+/// A `$` and the identifier directly following it are one `T_VARIABLE`, `++` and `--` are
+/// one token each, and a name of several parts is one `T_NAME_*`, as PHP lexes them.
+fn php_token_names(tokens: &[Token]) -> Vec<String> {
+    let tokens: Vec<&Token> = tokens
+        .iter()
+        .filter(|t| !matches!(t.0, TokenType::Whitespace | TokenType::LF(_)))
+        .collect();
+
+    let mut names = Vec::new();
+    let mut i = 0;
+    while i < tokens.len() {
+        let token = tokens[i];
+        let next = tokens.get(i + 1).filter(|t| t.1.start == token.1.end);
+        i += 1;
+
+        let name = match (&token.0, token.2.as_deref()) {
+            (TokenType::Variable, _) if matches!(next, Some(t) if t.0 == TokenType::Identifier) => {
+                i += 1;
+                "T_VARIABLE".to_string()
+            }
+            (TokenType::Operator, Some(op @ ("+" | "-")))
+                if next.is_some_and(|t| t.2.as_deref() == Some(op)) =>
+            {
+                i += 1;
+                if op == "+" { "T_INC" } else { "T_DEC" }.to_string()
+            }
+            (TokenType::Identifier | TokenType::Backslash, _) => {
+                let fully_qualified = token.0 == TokenType::Backslash;
+                let mut parts = 1;
+                while tokens.get(i).is_some_and(|t| {
+                    t.1.start == tokens[i - 1].1.end
+                        && matches!(t.0, TokenType::Identifier | TokenType::Backslash)
+                }) {
+                    parts += 1;
+                    i += 1;
+                }
+                match (fully_qualified, parts, token.2.as_deref()) {
+                    (true, 1, _) => "\\".to_string(),
+                    (true, ..) => "T_NAME_FULLY_QUALIFIED".to_string(),
+                    (false, 1, Some(word)) => PHP_RESERVED_WORDS
+                        .iter()
+                        .find(|(w, _)| w.eq_ignore_ascii_case(word))
+                        .map_or("T_STRING", |(_, name)| name)
+                        .to_string(),
+                    (false, ..) => "T_NAME_QUALIFIED".to_string(),
+                }
+            }
+            (TokenType::Operator, Some(op)) => PHP_OPERATORS
+                .iter()
+                .find(|(o, _)| *o == op)
+                .map_or(op, |(_, name)| name)
+                .to_string(),
+            (TokenType::OpenTag, Some("<?=")) => "T_OPEN_TAG_WITH_ECHO".to_string(),
+            (TokenType::OpenTag, _) => "T_OPEN_TAG".to_string(),
+            (TokenType::CloseTag, _) => "T_CLOSE_TAG".to_string(),
+            (TokenType::InlineHtml, _) => "T_INLINE_HTML".to_string(),
+            (TokenType::Comment, Some(comment)) if comment.starts_with("/**") => {
+                "T_DOC_COMMENT".to_string()
+            }
+            (TokenType::Comment, _) => "T_COMMENT".to_string(),
+            (TokenType::Keyword(keyword), _) => keyword_name(*keyword),
+            (TokenType::Boolean | TokenType::Null, _) => "T_STRING".to_string(),
+            (TokenType::NumericalLit(Numeric::Float(_)), _) => "T_DNUMBER".to_string(),
+            (TokenType::NumericalLit(_), _) => "T_LNUMBER".to_string(),
+            (TokenType::StringLit(StringType::Single | StringType::Double), _) => {
+                "T_CONSTANT_ENCAPSED_STRING".to_string()
+            }
+            (TokenType::Accessor(AccessType::StaticMember), _) => "T_DOUBLE_COLON".to_string(),
+            (TokenType::Accessor(AccessType::ReferenceMember), _) => {
+                "T_OBJECT_OPERATOR".to_string()
+            }
+            (TokenType::Accessor(AccessType::NullsafeMember), _) => {
+                "T_NULLSAFE_OBJECT_OPERATOR".to_string()
+            }
+            (TokenType::Ellipsis, _) => "T_ELLIPSIS".to_string(),
+            (TokenType::AttributeStart, _) => "T_ATTRIBUTE".to_string(),
+            (TokenType::EOF, _) => continue,
+            (kind, _) => token
+                .text()
+                .unwrap_or_else(|| panic!("no PHP token for {kind:?}"))
+                .into_owned(),
+        };
+        names.push(name);
+    }
+    names
+}
+
+/// Asserts the script lexes to the tokens `token_get_all` gives, without any `T_WHITESPACE`.
+fn assert_conforms(script: &str, expected: &[&str]) {
+    let tokens = Lexer::new(script)
+        .html_mode(HtmlMode::Opaque)
+        .tokenize()
+        .unwrap();
+    assert_eq!(php_token_names(&tokens), expected, "lexing {script:?}");
+}
+
+#[test]
+pub fn echo_string() {
+    assert_conforms(
+        "<?php echo 'hi', \"there\";",
+        &[
+            "T_OPEN_TAG",
+            "T_ECHO",
+            "T_CONSTANT_ENCAPSED_STRING",
+            ",",
+            "T_CONSTANT_ENCAPSED_STRING",
+            ";",
+        ],
+    );
+}
+
+#[test]
+pub fn arithmetic_assignment() {
+    assert_conforms(
+        "<?php $a = 1 + 2.5 * $b;",
+        &[
+            "T_OPEN_TAG",
+            "T_VARIABLE",
+            "=",
+            "T_LNUMBER",
+            "+",
+            "T_DNUMBER",
+            "*",
+            "T_VARIABLE",
+            ";",
+        ],
+    );
+}
+
+#[test]
+pub fn number_formats() {
+    assert_conforms(
+        "<?php $n = 0x1F + 0b11 + 1_000 + 1e3 + 07;",
+        &[
+            "T_OPEN_TAG",
+            "T_VARIABLE",
+            "=",
+            "T_LNUMBER",
+            "+",
+            "T_LNUMBER",
+            "+",
+            "T_LNUMBER",
+            "+",
+            "T_DNUMBER",
+            "+",
+            "T_LNUMBER",
+            ";",
+        ],
+    );
+}
+
+#[test]
+pub fn function_declaration() {
+    assert_conforms(
+        "<?php function add(int $a, int $b): int { return $a + $b; }",
+        &[
+            "T_OPEN_TAG",
+            "T_FUNCTION",
+            "T_STRING",
+            "(",
+            "T_STRING",
+            "T_VARIABLE",
+            ",",
+            "T_STRING",
+            "T_VARIABLE",
+            ")",
+            ":",
+            "T_STRING",
+            "{",
+            "T_RETURN",
+            "T_VARIABLE",
+            "+",
+            "T_VARIABLE",
+            ";",
+            "}",
+        ],
+    );
+}
+
+#[test]
+pub fn class_declaration() {
+    assert_conforms(
+        "<?php class Foo extends Bar implements Baz { public const X = 1; private static ?int $y = null; }",
+        &[
+            "T_OPEN_TAG",
+            "T_CLASS",
+            "T_STRING",
+            "T_EXTENDS",
+            "T_STRING",
+            "T_IMPLEMENTS",
+            "T_STRING",
+            "{",
+            "T_PUBLIC",
+            "T_CONST",
+            "T_STRING",
+            "=",
+            "T_LNUMBER",
+            ";",
+            "T_PRIVATE",
+            "T_STATIC",
+            "?",
+            "T_STRING",
+            "T_VARIABLE",
+            "=",
+            "T_STRING",
+            ";",
+            "}",
+        ],
+    );
+}
+
+#[test]
+pub fn member_access() {
+    assert_conforms(
+        "<?php $o?->a->b(); Foo::bar(); static::$c;",
+        &[
+            "T_OPEN_TAG",
+            "T_VARIABLE",
+            "T_NULLSAFE_OBJECT_OPERATOR",
+            "T_STRING",
+            "T_OBJECT_OPERATOR",
+            "T_STRING",
+            "(",
+            ")",
+            ";",
+            "T_STRING",
+            "T_DOUBLE_COLON",
+            "T_STRING",
+            "(",
+            ")",
+            ";",
+            "T_STATIC",
+            "T_DOUBLE_COLON",
+            "T_VARIABLE",
+            ";",
+        ],
+    );
+}
+
+#[test]
+pub fn compound_operators() {
+    assert_conforms(
+        "<?php $a ??= $b ?? $c <=> $d; $i++; --$j; $k **= 2;",
+        &[
+            "T_OPEN_TAG",
+            "T_VARIABLE",
+            "T_COALESCE_EQUAL",
+            "T_VARIABLE",
+            "T_COALESCE",
+            "T_VARIABLE",
+            "T_SPACESHIP",
+            "T_VARIABLE",
+            ";",
+            "T_VARIABLE",
+            "T_INC",
+            ";",
+            "T_DEC",
+            "T_VARIABLE",
+            ";",
+            "T_VARIABLE",
+            "T_POW_EQUAL",
+            "T_LNUMBER",
+            ";",
+        ],
+    );
+}
+
+#[test]
+pub fn control_flow() {
+    assert_conforms(
+        "<?php foreach ($xs as $k => $v) { if ($v === 1 && !$k) continue; }",
+        &[
+            "T_OPEN_TAG",
+            "T_FOREACH",
+            "(",
+            "T_VARIABLE",
+            "T_AS",
+            "T_VARIABLE",
+            "T_DOUBLE_ARROW",
+            "T_VARIABLE",
+            ")",
+            "{",
+            "T_IF",
+            "(",
+            "T_VARIABLE",
+            "T_IS_IDENTICAL",
+            "T_LNUMBER",
+            "T_BOOLEAN_AND",
+            "!",
+            "T_VARIABLE",
+            ")",
+            "T_CONTINUE",
+            ";",
+            "}",
+        ],
+    );
+}
+
+#[test]
+pub fn comments() {
+    assert_conforms(
+        "<?php // line\n/** doc */\n# hash\n/* block */ $a;",
+        &[
+            "T_OPEN_TAG",
+            "T_COMMENT",
+            "T_DOC_COMMENT",
+            "T_COMMENT",
+            "T_COMMENT",
+            "T_VARIABLE",
+            ";",
+        ],
+    );
+}
+
+#[test]
+pub fn qualified_names() {
+    assert_conforms(
+        "<?php namespace App\\Models; use \\Foo\\Bar; new Models\\User();",
+        &[
+            "T_OPEN_TAG",
+            "T_NAMESPACE",
+            "T_NAME_QUALIFIED",
+            ";",
+            "T_USE",
+            "T_NAME_FULLY_QUALIFIED",
+            ";",
+            "T_NEW",
+            "T_NAME_QUALIFIED",
+            "(",
+            ")",
+            ";",
+        ],
+    );
+}
+
+#[test]
+pub fn attributes_and_arrow_functions() {
+    assert_conforms(
+        "<?php #[Attr] fn(...$args) => @f(...$args);",
+        &[
+            "T_OPEN_TAG",
+            "T_ATTRIBUTE",
+            "T_STRING",
+            "]",
+            "T_FN",
+            "(",
+            "T_ELLIPSIS",
+            "T_VARIABLE",
+            ")",
+            "T_DOUBLE_ARROW",
+            "@",
+            "T_STRING",
+            "(",
+            "T_ELLIPSIS",
+            "T_VARIABLE",
+            ")",
+            ";",
+        ],
+    );
+}
+
+#[test]
+pub fn match_and_print() {
+    assert_conforms(
+        "<?php $r = match(true) { default => print 'x', }; $t = true and false;",
+        &[
+            "T_OPEN_TAG",
+            "T_VARIABLE",
+            "=",
+            "T_MATCH",
+            "(",
+            "T_STRING",
+            ")",
+            "{",
+            "T_DEFAULT",
+            "T_DOUBLE_ARROW",
+            "T_PRINT",
+            "T_CONSTANT_ENCAPSED_STRING",
+            ",",
+            "}",
+            ";",
+            "T_VARIABLE",
+            "=",
+            "T_STRING",
+            "T_LOGICAL_AND",
+            "T_STRING",
+            ";",
+        ],
+    );
+}
+
+#[test]
+pub fn inline_html() {
+    assert_conforms(
+        "<p><?= $x ?>\n</p>",
+        &[
+            "T_INLINE_HTML",
+            "T_OPEN_TAG_WITH_ECHO",
+            "T_VARIABLE",
+            "T_CLOSE_TAG",
+            "T_INLINE_HTML",
+        ],
+    );
+}
@@ -0,0 +1,55 @@
+use compiler::syntax::lex::token::TokenType;
+
+use super::{kinds, lex};
+
+#[test]
+pub fn leading_skip() {
+    assert_eq!(
+        kinds("[, $b] = $arr;")[..5],
+        [
+            TokenType::LeftBracket,
+            TokenType::Comma,
+            TokenType::Variable,
+            TokenType::Identifier,
+            TokenType::RightBracket,
+        ]
+    );
+    assert_eq!(
+        kinds("list(, $b) = $arr;")[..6],
+        [
+            TokenType::Identifier,
+            TokenType::LeftParenthesis,
+            TokenType::Comma,
+            TokenType::Variable,
+            TokenType::Identifier,
+            TokenType::RightParenthesis,
+        ]
+    );
+}
+
+#[test]
+pub fn middle_skip() {
+    let expected = [
+        TokenType::Variable,
+        TokenType::Identifier,
+        TokenType::Comma,
+        TokenType::Comma,
+        TokenType::Variable,
+        TokenType::Identifier,
+    ];
+
+    assert_eq!(kinds("[$a, , $c] = $arr;")[1..7], expected);
+    assert_eq!(kinds("[$a,,$c] = $arr;")[1..7], expected);
+    assert_eq!(kinds("list($a, , $c) = $arr;")[2..8], expected);
+}
+
+#[test]
+pub fn skipped_commas_keep_their_spans() {
+    let commas: Vec<_> = lex("[, , $c]")
+        .iter()
+        .filter(|t| t.kind() == TokenType::Comma)
+        .map(|t| t.range())
+        .collect();
+
+    assert_eq!(commas, vec![1..2, 3..4]);
+}
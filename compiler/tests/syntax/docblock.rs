@@ -0,0 +1,168 @@
+use compiler::syntax::lex::docblock::{parse_docblock, tokenize_docblock, DocTag, DocToken};
+
+fn ty(name: &str) -> DocToken {
+    DocToken::Type(name.to_string())
+}
+
+#[test]
+pub fn generic_param() {
+    let comment = "/**\n * @param array<int, string> $x The values.\n */";
+
+    assert_eq!(
+        tokenize_docblock(comment),
+        vec![
+            DocToken::Tag("@param".to_string()),
+            ty("array"),
+            DocToken::GenericOpen,
+            ty("int"),
+            DocToken::Comma,
+            ty("string"),
+            DocToken::GenericClose,
+            DocToken::Variable("$x".to_string()),
+            DocToken::Text("The values.".to_string()),
+        ]
+    );
+}
+
+#[test]
+pub fn nested_generics() {
+    assert_eq!(
+        tokenize_docblock("/** @return Map<string, list<?\\Foo\\Bar>>|null */"),
+        vec![
+            DocToken::Tag("@return".to_string()),
+            ty("Map"),
+            DocToken::GenericOpen,
+            ty("string"),
+            DocToken::Comma,
+            ty("list"),
+            DocToken::GenericOpen,
+            DocToken::Nullable,
+            ty("\\Foo\\Bar"),
+            DocToken::GenericClose,
+            DocToken::GenericClose,
+            DocToken::Union,
+            ty("null"),
+        ]
+    );
+}
+
+#[test]
+pub fn summary_and_array_type() {
+    assert_eq!(
+        tokenize_docblock("/**\n * Sums the values.\n *\n * @var int[] $values\n */"),
+        vec![
+            DocToken::Text("Sums the values.".to_string()),
+            DocToken::Tag("@var".to_string()),
+            ty("int"),
+            DocToken::Array,
+            DocToken::Variable("$values".to_string()),
+        ]
+    );
+}
+
+#[test]
+pub fn tag_without_type() {
+    assert_eq!(
+        tokenize_docblock("/**\n * @deprecated Use $list.\n * @see Foo::bar()\n */"),
+        vec![
+            DocToken::Tag("@deprecated".to_string()),
+            DocToken::Text("Use $list.".to_string()),
+            DocToken::Tag("@see".to_string()),
+            DocToken::Text("Foo::bar()".to_string()),
+        ]
+    );
+}
+
+#[test]
+pub fn variadic_and_reference_params() {
+    assert_eq!(
+        tokenize_docblock("/**\n * @param int ...$xs the values\n * @psalm-param array &$out\n */"),
+        vec![
+            DocToken::Tag("@param".to_string()),
+            ty("int"),
+            DocToken::Variable("...$xs".to_string()),
+            DocToken::Text("the values".to_string()),
+            DocToken::Tag("@psalm-param".to_string()),
+            ty("array"),
+            DocToken::Variable("&$out".to_string()),
+        ]
+    );
+}
+
+#[test]
+pub fn less_than_in_text_is_not_a_type() {
+    assert_eq!(
+        tokenize_docblock("/** Returns 1 if $a < $b. */"),
+        vec![DocToken::Text("Returns 1 if $a < $b.".to_string())]
+    );
+}
+
+#[test]
+pub fn parse_function_docblock() {
+    let comment = "/**
+     * Adds two numbers.
+     *
+     * Both numbers must be finite,
+     * or the sum is `NAN`.
+     *
+     * @param int|float $a The first number.
+     * @param array<int, string> $b
+     *     The second number,
+     *     spanning lines.
+     * @param $c
+     * @return int|float The sum.
+     * @throws \\InvalidArgumentException
+     */";
+    let block = parse_docblock(comment);
+
+    assert_eq!(block.summary.as_deref(), Some("Adds two numbers."));
+    assert_eq!(
+        block.description.as_deref(),
+        Some("Both numbers must be finite,\nor the sum is `NAN`.")
+    );
+    assert_eq!(
+        block.params,
+        vec![
+            DocTag {
+                ty: Some("int|float".to_string()),
+                name: Some("$a".to_string()),
+                description: Some("The first number.".to_string()),
+            },
+            DocTag {
+                ty: Some("array<int,string>".to_string()),
+                name: Some("$b".to_string()),
+                description: Some("The second number,\nspanning lines.".to_string()),
+            },
+            DocTag {
+                ty: None,
+                name: Some("$c".to_string()),
+                description: None,
+            },
+        ]
+    );
+    assert_eq!(
+        block.returns,
+        Some(DocTag {
+            ty: Some("int|float".to_string()),
+            name: None,
+            description: Some("The sum.".to_string()),
+        })
+    );
+    assert_eq!(block.throws.len(), 1);
+    assert_eq!(
+        block.throws[0].ty.as_deref(),
+        Some("\\InvalidArgumentException")
+    );
+}
+
+#[test]
+pub fn parse_var_docblock() {
+    let block = parse_docblock("/**\n * @deprecated Use $list.\n * @var ?Foo[] $items\n */");
+
+    assert_eq!(block.summary, None);
+    assert_eq!(block.vars.len(), 1);
+    assert_eq!(block.vars[0].ty.as_deref(), Some("?Foo[]"));
+    assert_eq!(block.vars[0].name.as_deref(), Some("$items"));
+    assert_eq!(block.vars[0].description, None);
+    assert!(block.params.is_empty() && block.returns.is_none());
+}
@@ -0,0 +1,176 @@
+use compiler::syntax::lex::{
+    error::{LexError, Limit},
+    Lexer,
+};
+
+fn lex_error(script: &str) -> LexError {
+    let error = Lexer::new(script)
+        .tokenize()
+        .expect_err("script should not lex");
+    LexError::from_io(&error)
+        .expect("error should be a lex error")
+        .clone()
+}
+
+#[test]
+pub fn unexpected_char() {
+    let error = lex_error("$a = 1 € 2;");
+
+    assert_eq!(
+        error,
+        LexError::UnexpectedChar {
+            character: '€',
+            span: 7..10,
+            hint: None,
+        }
+    );
+    assert!(error.to_string().contains('€'));
+}
+
+#[test]
+pub fn unexpected_char_hint() {
+    let error = lex_error("$a = “hello”;");
+
+    assert_eq!(
+        error,
+        LexError::UnexpectedChar {
+            character: '“',
+            span: 5..8,
+            hint: Some("did you mean to use a double quote `\"`?"),
+        }
+    );
+}
+
+#[test]
+pub fn unexpected_char_at_start() {
+    let error = lex_error("€ hello");
+
+    assert_eq!(error.span(), 0..3);
+    assert!(error.to_string().contains("<?php"));
+}
+
+#[test]
+pub fn backtick_does_not_panic() {
+    let error = lex_error("$a = `ls`;");
+
+    assert_eq!(error.span(), 5..6);
+}
+
+#[test]
+pub fn coalesce_arrow_typo() {
+    let error = lex_error("$a ??-> $b;");
+
+    assert_eq!(
+        error,
+        LexError::InvalidOperator {
+            operator: "??->",
+            span: 3..7,
+            hint: "did you mean the nullsafe operator `?->`?",
+        }
+    );
+    assert!(error.to_string().contains("`?->`"));
+    assert!(Lexer::new("$a ?? -$b; $a?->b;").tokenize().is_ok());
+}
+
+#[test]
+pub fn arrow_comparison_typo() {
+    let error = lex_error("$a =>= $b;");
+
+    assert_eq!(error.span(), 3..6);
+    assert!(error.to_string().ends_with("did you mean `>=`?"));
+    assert!(Lexer::new("[$a => $b >= $c]").tokenize().is_ok());
+}
+
+#[test]
+pub fn variable_variable_variable_is_valid() {
+    // unlike the typos above, `$$$a` is a valid variable variable.
+    assert!(Lexer::new("$$$a = 1;").tokenize().is_ok());
+}
+
+#[test]
+pub fn input_length_limit() {
+    let mut lexer = Lexer::new("$a = 1;").max_input_len(4);
+    let error = lexer.tokenize().unwrap_err();
+
+    assert_eq!(
+        LexError::from_io(&error),
+        Some(&LexError::LimitExceeded {
+            limit: Limit::InputLength,
+            max: 4,
+            span: 4..7,
+        })
+    );
+    assert!(Lexer::new("$a = 1;").max_input_len(7).tokenize().is_ok());
+}
+
+#[test]
+pub fn nesting_depth_limit() {
+    let script = "foo([{$a}]);";
+    let error = Lexer::new(script)
+        .max_nesting_depth(2)
+        .tokenize()
+        .unwrap_err();
+
+    assert_eq!(
+        LexError::from_io(&error),
+        Some(&LexError::LimitExceeded {
+            limit: Limit::NestingDepth,
+            max: 2,
+            span: 5..6,
+        })
+    );
+    assert!(Lexer::new(script).max_nesting_depth(3).tokenize().is_ok());
+}
+
+#[test]
+pub fn limits_off_by_default() {
+    let script = "(".repeat(1000) + &")".repeat(1000);
+
+    assert!(Lexer::new(&script).tokenize().is_ok());
+}
+
+#[test]
+pub fn invalid_utf8() {
+    let error = Lexer::from_bytes(b"$a = 'caf\xe9';").err().unwrap();
+
+    assert_eq!(
+        LexError::from_io(&error),
+        Some(&LexError::InvalidUtf8 { span: 9..10 })
+    );
+    assert_eq!(error.to_string(), "Invalid UTF-8 sequence at 9 until 10");
+}
+
+#[test]
+pub fn truncated_utf8() {
+    // the first two bytes of `€`.
+    let error = Lexer::from_bytes(b"$a = 1;\xe2\x82").err().unwrap();
+
+    assert_eq!(
+        LexError::from_io(&error),
+        Some(&LexError::InvalidUtf8 { span: 7..9 })
+    );
+}
+
+#[test]
+pub fn valid_utf8_bytes() {
+    let tokens = Lexer::from_bytes("$a = 'café';".as_bytes())
+        .and_then(|mut lexer| lexer.tokenize())
+        .unwrap();
+
+    assert_eq!(tokens, Lexer::new("$a = 'café';").tokenize().unwrap());
+}
+
+#[test]
+pub fn latin1_bytes() {
+    let tokens = Lexer::from_latin1(b"$a = 'caf\xe9';").tokenize().unwrap();
+
+    assert_eq!(tokens[5].value().as_deref(), Some("café"));
+}
+
+#[test]
+pub fn invalid_number() {
+    let error = lex_error("$a = 1__000;");
+
+    assert_eq!(error, LexError::InvalidNumber { span: 5..11 });
+    assert_eq!(error.to_string(), "Invalid numeric literal at 5 until 11");
+}
@@ -0,0 +1,173 @@
+use compiler::syntax::lex::{
+    format::{code_skeleton, debug_tokens, minify, normalize_spacing, StringMask},
+    Lexer,
+};
+
+#[test]
+pub fn debug_token_columns() {
+    let tokens = Lexer::new("$a = 'b';").tokenize().unwrap();
+
+    assert_eq!(
+        debug_tokens(&tokens),
+        concat!(
+            "0  Variable           0..1  \"$\"\n",
+            "1  Identifier         1..2  \"a\"\n",
+            "2  Whitespace         2..3  \" \"\n",
+            "3  Operator           3..4  \"=\"\n",
+            "4  Whitespace         4..5  \" \"\n",
+            "5  StringLit(Single)  5..8  \"b\"\n",
+            "6  EOS                8..9  \";\"\n",
+        )
+    );
+}
+
+#[test]
+pub fn debug_tokens_empty() {
+    assert_eq!(debug_tokens(&[]), "");
+}
+
+fn normalized(script: &str) -> String {
+    normalize_spacing(&Lexer::new(script).tokenize().unwrap(), script)
+}
+
+#[test]
+pub fn binary_operator_spacing() {
+    assert_eq!(normalized("1+2"), "1 + 2");
+    assert_eq!(normalized("$a  =$b.'c'"), "$a = $b . 'c'");
+    assert_eq!(normalized("$a??=[1=>2];"), "$a ??= [1 => 2];");
+}
+
+#[test]
+pub fn unary_operator_spacing() {
+    assert_eq!(normalized("- $x"), "-$x");
+    assert_eq!(normalized("$a=- $x"), "$a = -$x");
+    assert_eq!(normalized("if (! $a&&!$b)"), "if (!$a && !$b)");
+    assert_eq!(normalized("foo(& $x)"), "foo(&$x)");
+    assert_eq!(normalized("$a-1"), "$a - 1");
+}
+
+#[test]
+pub fn spacing_keeps_line_breaks_and_increments() {
+    assert_eq!(normalized("$a\n    +$b"), "$a\n    + $b");
+    assert_eq!(normalized("$a +\n    $b"), "$a +\n    $b");
+    assert_eq!(normalized("$i++ +1; --$j;"), "$i++ + 1; --$j;");
+}
+
+#[test]
+pub fn spacing_keeps_strings_verbatim() {
+    assert_eq!(normalized(r#"'it\'s'.":\"\\""#), r#"'it\'s' . ":\"\\""#);
+    assert_eq!(normalized(r#"$a="\x41".'\x41'"#), r#"$a = "\x41" . '\x41'"#);
+    assert_eq!(
+        normalized("$a=<<<EOT\n  \\u{1F600} $b\n  EOT;"),
+        "$a = <<<EOT\n  \\u{1F600} $b\n  EOT;"
+    );
+}
+
+#[test]
+pub fn minify_function() {
+    assert_eq!(
+        minify("<?php  function  f(){}").unwrap(),
+        "<?php function f(){}"
+    );
+}
+
+#[test]
+pub fn minify_keeps_needed_spaces() {
+    assert_eq!(minify("<?php echo 1;").unwrap(), "<?php echo 1;");
+    assert_eq!(minify("<?php $a = $b + +$c;").unwrap(), "<?php $a=$b+ +$c;");
+    assert_eq!(minify("<?php $i ++ ; $j++;").unwrap(), "<?php $i++;$j++;");
+    assert_eq!(
+        minify("<?php return $x ?? null ;").unwrap(),
+        "<?php return$x??null;"
+    );
+}
+
+#[test]
+pub fn minify_strips_comments() {
+    let script = "<?php\n// setup\n$a = 1; /* one */\n# done\n/** doc */\necho $a;\n";
+
+    assert_eq!(minify(script).unwrap(), "<?php $a=1;echo$a;");
+}
+
+#[test]
+pub fn minify_keeps_strings_verbatim() {
+    let script = "<?php echo 'a  b' . \"\\t{$c}  \";\n$x = <<<EOT\n  two  spaces\n  EOT;\n";
+
+    assert_eq!(
+        minify(script).unwrap(),
+        "<?php echo'a  b'.\"\\t{$c}  \";$x=<<<EOT\n  two  spaces\n  EOT;"
+    );
+}
+
+#[test]
+pub fn minify_keeps_shebang_line() {
+    assert_eq!(
+        minify("#!/usr/bin/env php\n<?php echo 1;").unwrap(),
+        "#!/usr/bin/env php\n<?php echo 1;"
+    );
+}
+
+#[test]
+pub fn skeleton_removes_comments() {
+    let script = "<?php\n// eval($x)\n$a = 1; /* exec */ $b = 2; # system\n";
+    assert_eq!(
+        code_skeleton(script, StringMask::Collapse).unwrap(),
+        "<?php\n\n$a = 1;  $b = 2; \n"
+    );
+
+    let skeleton = code_skeleton(script, StringMask::KeepLength).unwrap();
+    assert_eq!(skeleton.len(), script.len());
+    assert!(!skeleton.contains("eval") && !skeleton.contains("exec"));
+    assert_eq!(skeleton.lines().count(), script.lines().count());
+    // the tokens either side of a dropped comment are kept apart.
+    assert_eq!(
+        code_skeleton("$a/**/$b", StringMask::Collapse).unwrap(),
+        "$a $b"
+    );
+}
+
+#[test]
+pub fn skeleton_masks_strings() {
+    let script = "eval('eval'); $s = \"rm -rf $dir\" . '';";
+    assert_eq!(
+        code_skeleton(script, StringMask::KeepLength).unwrap(),
+        "eval('xxxx'); $s = \"xxxxxxxxxxx\" . '';"
+    );
+    assert_eq!(
+        code_skeleton(script, StringMask::Collapse).unwrap(),
+        "eval('x'); $s = \"x\" . '';"
+    );
+}
+
+#[test]
+pub fn skeleton_masks_heredocs() {
+    let script = "$a = <<<SQL\n  DROP TABLE users;\n  SQL;\n$b = <<<'EOT'\nexec\r\nEOT;";
+    assert_eq!(
+        code_skeleton(script, StringMask::KeepLength).unwrap(),
+        "$a = <<<SQL\nxxxxxxxxxxxxxxxxxxx\n  SQL;\n$b = <<<'EOT'\nxxxx\r\nEOT;"
+    );
+    assert_eq!(
+        code_skeleton(script, StringMask::Collapse).unwrap(),
+        "$a = <<<SQL\nx\n  SQL;\n$b = <<<'EOT'\nx\r\nEOT;"
+    );
+}
+
+#[test]
+pub fn minify_keeps_inline_html() {
+    let script = "<p>Item #1   and  #2</p><?php  echo  1; ?>\n<b>x</b>";
+
+    assert_eq!(
+        minify(script).unwrap(),
+        "<p>Item #1   and  #2</p><?php echo 1;?>\n<b>x</b>"
+    );
+    assert_eq!(minify("<p>no tags</p>").unwrap(), "<p>no tags</p>");
+}
+
+#[test]
+pub fn minify_line_comment_ends_at_close_tag() {
+    assert_eq!(minify("<?php // c ?> hello").unwrap(), "<?php ?> hello");
+    assert_eq!(
+        minify("<ul>\n<?php # loop ?>\n  <li><?= $a /* x */ ?></li>\n</ul>").unwrap(),
+        "<ul>\n<?php ?>\n  <li><?=$a?></li>\n</ul>"
+    );
+}
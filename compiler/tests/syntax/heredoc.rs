@@ -0,0 +1,69 @@
+use std::time::{Duration, Instant};
+
+use compiler::syntax::lex::token::{Location, StringType, TokenType};
+
+use super::lex;
+
+#[test]
+pub fn heredoc() {
+    let tokens = lex("$a = <<<END\n  one\n    two\n  END;\n$b;");
+
+    assert_eq!(tokens[3].kind(), TokenType::StringLit(StringType::HereDoc));
+    assert_eq!(tokens[3].value().as_deref(), Some("one\n  two"));
+    assert_eq!(tokens[3].location().end, Location::new(4, 6));
+    assert_eq!(tokens[4].kind(), TokenType::EOS);
+    assert_eq!(tokens[5].location().start, Location::new(5, 1));
+}
+
+#[test]
+pub fn quoted_heredoc() {
+    let tokens = lex("<<<\"END\"\nbody\nEND;");
+
+    assert_eq!(tokens[0].kind(), TokenType::StringLit(StringType::HereDoc));
+    assert_eq!(tokens[0].value().as_deref(), Some("body"));
+}
+
+#[test]
+pub fn nowdoc() {
+    let tokens = lex("<<< 'END'\r\n$not \\x41 parsed\r\nEND\r\n");
+
+    assert_eq!(tokens[0].kind(), TokenType::StringLit(StringType::NowDoc));
+    assert_eq!(tokens[0].value().as_deref(), Some("$not \\x41 parsed"));
+    assert_eq!(tokens.len(), 1);
+}
+
+#[test]
+pub fn closing_identifier_needs_boundary() {
+    let tokens = lex("<<<END\nENDING\nEND\n");
+
+    assert_eq!(tokens[0].value().as_deref(), Some("ENDING"));
+    assert_eq!(tokens.len(), 1);
+}
+
+#[test]
+pub fn empty_heredoc() {
+    let tokens = lex("<<<END\nEND;");
+
+    assert_eq!(tokens[0].value().as_deref(), Some(""));
+    assert_eq!(tokens[1].kind(), TokenType::EOS);
+}
+
+#[test]
+pub fn shift_is_not_heredoc() {
+    let tokens = lex("$a <<< $b");
+
+    assert_eq!(tokens[2].value().as_deref(), Some("<<"));
+}
+
+#[test]
+pub fn long_heredoc() {
+    let body = vec!["    a line of the heredoc"; 10_000].join("\n");
+    let script = format!("$a = <<<EOT\n{}\n    EOT;\n", body);
+
+    let start = Instant::now();
+    let tokens = lex(&script);
+
+    assert!(start.elapsed() < Duration::from_secs(2));
+    assert_eq!(tokens[3].value().unwrap().lines().count(), 10_000);
+    assert_eq!(tokens[3].location().end.line, 10_002);
+}
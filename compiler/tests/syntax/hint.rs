@@ -0,0 +1,333 @@
+use compiler::syntax::lex::{
+    hint::{
+        colon_role, is_nullable_type, is_short_ternary, is_type_operator, operator_precedence,
+        special_type, ColonRole, SpecialType,
+    },
+    token::{Token, TokenType},
+    Lexer,
+};
+
+fn colon_roles(script: &str) -> Vec<ColonRole> {
+    let tokens: Vec<Token> = Lexer::new(script).tokenize().unwrap();
+    (0..tokens.len())
+        .filter_map(|i| colon_role(&tokens, i))
+        .collect()
+}
+
+#[test]
+pub fn named_argument() {
+    let kinds: Vec<TokenType> = super::lex("foo(x: 1)").iter().map(|t| t.kind()).collect();
+
+    assert!(matches!(
+        kinds.as_slice(),
+        [
+            TokenType::Identifier,
+            TokenType::LeftParenthesis,
+            TokenType::Identifier,
+            TokenType::Colon,
+            TokenType::NumericalLit(_),
+            TokenType::RightParenthesis,
+        ]
+    ));
+    assert_eq!(colon_roles("foo(x: 1)"), vec![ColonRole::NamedArgument]);
+}
+
+#[test]
+pub fn named_arguments() {
+    assert_eq!(
+        colon_roles("foo(width: 10, height: 5)"),
+        vec![ColonRole::NamedArgument, ColonRole::NamedArgument]
+    );
+}
+
+#[test]
+pub fn ternary_not_named_argument() {
+    assert_eq!(colon_roles("foo($a ? x : 1)"), vec![ColonRole::Ternary]);
+    assert_eq!(colon_roles("$a ? b : c;"), vec![ColonRole::Ternary]);
+}
+
+#[test]
+pub fn nested_ternary_in_named_argument() {
+    assert_eq!(
+        colon_roles("foo(x: $a ? b : c)"),
+        vec![ColonRole::NamedArgument, ColonRole::Ternary]
+    );
+}
+
+fn precedences(script: &str) -> Vec<u8> {
+    super::lex(script)
+        .iter()
+        .filter_map(operator_precedence)
+        .collect()
+}
+
+#[test]
+pub fn instanceof_binds_before_not() {
+    // `!$x instanceof Foo` is `!($x instanceof Foo)`.
+    let [not, instanceof] = precedences("!$x instanceof Foo")[..] else {
+        panic!("expected two operators");
+    };
+
+    assert!(instanceof > not);
+}
+
+#[test]
+pub fn keyword_operator_precedence() {
+    let [new, instanceof, and] = precedences("new Foo instanceof Bar && $y")[..] else {
+        panic!("expected three operators");
+    };
+    let [clone, not] = precedences("clone !$a")[..] else {
+        panic!("expected two operators");
+    };
+
+    assert_eq!(new, clone);
+    assert!(new > instanceof);
+    assert!(instanceof > not);
+    assert!(not > and);
+}
+
+#[test]
+pub fn print_binds_like_yield() {
+    let [print, and] = precedences("print $a and $b")[..] else {
+        panic!("expected two operators");
+    };
+    let [yield_] = precedences("yield $a")[..] else {
+        panic!("expected one operator");
+    };
+
+    assert_eq!(print, yield_);
+    assert!(print > and);
+}
+
+#[test]
+pub fn word_operator_precedence() {
+    let [assign, and, xor, or] = precedences("$a = $b and $c xor $d or $e")[..] else {
+        panic!("expected four operators");
+    };
+
+    assert!(assign > and);
+    assert!(and > xor);
+    assert!(xor > or);
+}
+
+#[test]
+pub fn binary_operator_precedence() {
+    let ranked = precedences("$a = $b ?? $c || $d && $e == $f . $g + $h * $i ** $j");
+
+    assert_eq!(ranked.len(), 9);
+    assert!(
+        ranked.windows(2).all(|pair| pair[0] < pair[1]),
+        "{:?}",
+        ranked
+    );
+}
+
+#[test]
+pub fn arrow_has_no_precedence() {
+    assert_eq!(precedences("$a => $b, $c"), vec![]);
+}
+
+fn short_ternaries(script: &str) -> Vec<usize> {
+    let tokens: Vec<Token> = Lexer::new(script).split_whitespace().tokenize().unwrap();
+    (0..tokens.len())
+        .filter(|i| is_short_ternary(&tokens, *i))
+        .map(|i| tokens[i].1.start)
+        .collect()
+}
+
+#[test]
+pub fn short_ternary() {
+    assert_eq!(short_ternaries("$a ?: $b"), vec![3]);
+    assert_eq!(colon_roles("$a ?: $b"), vec![ColonRole::Ternary]);
+}
+
+#[test]
+pub fn short_ternary_across_whitespace() {
+    assert_eq!(short_ternaries("$a ? : $b"), vec![3]);
+    assert_eq!(short_ternaries("$a\n    ?\n: $b"), vec![7]);
+    assert_eq!(short_ternaries("$a ? /* none */ : $b"), vec![3]);
+    assert_eq!(colon_roles("$a\n    ?\n: $b"), vec![ColonRole::Ternary]);
+}
+
+#[test]
+pub fn full_ternary_is_not_short() {
+    assert_eq!(short_ternaries("$a ? $b : $c"), vec![]);
+}
+
+fn return_type(script: &str) -> Option<SpecialType> {
+    let tokens: Vec<Token> = Lexer::new(script).tokenize().unwrap();
+    let colon =
+        (0..tokens.len()).find(|&i| colon_role(&tokens, i) == Some(ColonRole::ReturnType))?;
+    tokens[colon + 1..]
+        .iter()
+        .find(|t| !matches!(t.kind(), TokenType::Whitespace))
+        .and_then(special_type)
+}
+
+#[test]
+pub fn static_return_type() {
+    assert_eq!(
+        return_type("function foo(): static {}"),
+        Some(SpecialType::Static)
+    );
+    assert_eq!(
+        return_type("public static function create(): static {}"),
+        Some(SpecialType::Static)
+    );
+}
+
+#[test]
+pub fn self_return_type() {
+    assert_eq!(
+        return_type("function foo(): self {}"),
+        Some(SpecialType::SelfType)
+    );
+    assert_eq!(
+        return_type("fn() : SELF => $this"),
+        Some(SpecialType::SelfType)
+    );
+    assert_eq!(
+        return_type("function () use ($a): parent {}"),
+        Some(SpecialType::Parent)
+    );
+    assert_eq!(
+        return_type("function &stop(): never {}"),
+        Some(SpecialType::Never)
+    );
+}
+
+#[test]
+pub fn call_in_ternary_is_not_return_type() {
+    assert_eq!(
+        colon_roles("$a ? foo() : self::X"),
+        vec![ColonRole::Ternary]
+    );
+    assert_eq!(return_type("$a ? foo() : self::X"), None);
+}
+
+/// The text of every operator in the script that joins types.
+fn type_operators(script: &str) -> Vec<String> {
+    let tokens: Vec<Token> = Lexer::new(script).tokenize().unwrap();
+    (0..tokens.len())
+        .filter(|&i| is_type_operator(&tokens, i))
+        .map(|i| format!("{}@{}", tokens[i].value().unwrap(), tokens[i].1.start))
+        .collect()
+}
+
+#[test]
+pub fn union_parameter_type() {
+    let kinds: Vec<TokenType> = super::lex("function f(int|string $x)")
+        .iter()
+        .map(|t| t.kind())
+        .collect();
+
+    assert_eq!(kinds[4], TokenType::Operator);
+    assert_eq!(type_operators("function f(int|string $x)"), vec!["|@14"]);
+    assert_eq!(
+        type_operators("function f(?Foo $a, \\A|null $b)"),
+        vec!["|@22"]
+    );
+}
+
+#[test]
+pub fn intersection_parameter_type() {
+    assert_eq!(type_operators("function f(A&B $y) {}"), vec!["&@12"]);
+    assert_eq!(
+        type_operators("function f((A&B)|null $y) {}"),
+        vec!["&@13", "|@16"]
+    );
+}
+
+#[test]
+pub fn union_return_and_property_types() {
+    assert_eq!(type_operators("function f(): int|false {}"), vec!["|@17"]);
+    assert_eq!(type_operators("public A|B $x;"), vec!["|@8"]);
+}
+
+#[test]
+pub fn bitwise_operators_are_not_types() {
+    assert!(type_operators("$a = $b | $c & $d;").is_empty());
+    assert!(type_operators("$flags = A|B;").is_empty());
+    // a reference parameter, not an intersection.
+    assert!(type_operators("function f(A &$y) {}").is_empty());
+}
+
+/// The offsets of every `?` that makes a type nullable.
+fn nullable_types(script: &str) -> Vec<usize> {
+    let tokens: Vec<Token> = Lexer::new(script).tokenize().unwrap();
+    (0..tokens.len())
+        .filter(|&i| is_nullable_type(&tokens, i))
+        .map(|i| tokens[i].1.start)
+        .collect()
+}
+
+#[test]
+pub fn nullable_parameter_and_return_types() {
+    let script = "function f(?int $x): ?string";
+    let tokens: Vec<(TokenType, Option<String>)> = super::lex(script)
+        .iter()
+        .map(|t| (t.kind(), t.value()))
+        .collect();
+
+    assert_eq!(tokens[3], (TokenType::QuestionMark, None));
+    assert_eq!(tokens[4], (TokenType::Identifier, Some("int".to_string())));
+    assert_eq!(tokens[9], (TokenType::QuestionMark, None));
+    assert_eq!(
+        tokens[10],
+        (TokenType::Identifier, Some("string".to_string()))
+    );
+    assert_eq!(nullable_types(script), vec![11, 21]);
+}
+
+#[test]
+pub fn nullable_property_types() {
+    assert_eq!(
+        nullable_types("class A { public ?Foo $a; private static ?\\B\\C $b; }"),
+        vec![17, 41]
+    );
+    assert_eq!(
+        nullable_types("fn(?A $a, ? B $b): ?static => $a"),
+        vec![3, 10, 19]
+    );
+}
+
+#[test]
+pub fn ternary_is_not_nullable() {
+    assert!(nullable_types("$a ? b : c; f($x ? Y : Z); $a ?-> b; $a ?? c;").is_empty());
+    assert!(nullable_types("$a = $b ?: C;").is_empty());
+}
+
+#[test]
+pub fn label_definition() {
+    assert_eq!(colon_roles("here: echo 1;"), vec![ColonRole::Label]);
+    assert_eq!(
+        colon_roles("<?php goto end; echo 1; end: echo 2;"),
+        vec![ColonRole::Label]
+    );
+    assert_eq!(
+        colon_roles("while (true) { again: if ($a) goto again; }"),
+        vec![ColonRole::Label]
+    );
+}
+
+#[test]
+pub fn case_is_not_label() {
+    assert_eq!(
+        colon_roles("switch ($a) { case 1: break; case FOO: break; default: here: break; }"),
+        vec![
+            ColonRole::Other,
+            ColonRole::Other,
+            ColonRole::Other,
+            ColonRole::Label
+        ]
+    );
+}
+
+#[test]
+pub fn ternary_is_not_label() {
+    assert_eq!(colon_roles("$a ? b : c;"), vec![ColonRole::Ternary]);
+    assert_eq!(
+        colon_roles("$a ? $b ? x : y : z;"),
+        vec![ColonRole::Ternary, ColonRole::Ternary]
+    );
+}
@@ -0,0 +1,61 @@
+use compiler::syntax::lex::{
+    error::LexError,
+    token::{Token, TokenType},
+    IdentifierStrictness, Lexer,
+};
+
+fn lex_with(script: &str, strictness: IdentifierStrictness) -> std::io::Result<Vec<Token>> {
+    Lexer::new(script)
+        .identifier_strictness(strictness)
+        .tokenize()
+}
+
+#[test]
+pub fn accented_variable() {
+    for strictness in [
+        IdentifierStrictness::PhpBytes,
+        IdentifierStrictness::Unicode,
+    ] {
+        let tokens = lex_with("$café = 1;", strictness).unwrap();
+        assert_eq!(tokens[1].kind(), TokenType::Identifier);
+        assert_eq!(tokens[1].value().as_deref(), Some("café"), "{strictness:?}");
+    }
+
+    let error = lex_with("$café = 1;", IdentifierStrictness::Ascii).unwrap_err();
+    assert!(matches!(
+        LexError::from_io(&error),
+        Some(LexError::UnexpectedChar {
+            character: 'é',
+            span,
+            ..
+        }) if *span == (4..6)
+    ));
+}
+
+#[test]
+pub fn non_ascii_start() {
+    let tokens = lex_with("$été + ÉTÉ;", IdentifierStrictness::PhpBytes).unwrap();
+    let names: Vec<_> = tokens
+        .iter()
+        .filter(|t| t.0 == TokenType::Identifier)
+        .filter_map(|t| t.value())
+        .collect();
+    assert_eq!(names, ["été", "ÉTÉ"]);
+
+    // only PHP allows any char that is not ASCII, including those that are not alphanumeric.
+    let tokens = lex_with("$a€ = 1;", IdentifierStrictness::PhpBytes).unwrap();
+    assert_eq!(tokens[1].value().as_deref(), Some("a€"));
+    assert!(lex_with("$a€ = 1;", IdentifierStrictness::Unicode).is_err());
+    assert!(lex_with("$été;", IdentifierStrictness::Unicode).is_err());
+}
+
+#[test]
+pub fn keyword_followed_by_non_ascii() {
+    let tokens = lex_with("classé", IdentifierStrictness::PhpBytes).unwrap();
+    assert_eq!(tokens.len(), 1);
+    assert_eq!(tokens[0].kind(), TokenType::Identifier);
+
+    let tokens = lex_with("class_1", IdentifierStrictness::Ascii).unwrap();
+    assert_eq!(tokens.len(), 1);
+    assert_eq!(tokens[0].kind(), TokenType::Identifier);
+}
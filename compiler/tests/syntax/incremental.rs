@@ -0,0 +1,152 @@
+use compiler::syntax::lex::{
+    incremental::{relex, token_ids},
+    token::TokenType,
+    HtmlMode, Lexer,
+};
+
+const SCRIPT: &str = "$a = 1;\n$b = 2;\n$c = 3;\n$d = 4;\n$e = 5;\n";
+
+#[test]
+pub fn relex_single_line() {
+    let old = Lexer::new(SCRIPT).tokenize().unwrap();
+
+    // `$c = 3;` becomes `$c = 300;`
+    let edit = 22..23;
+    let script = SCRIPT.replacen("3", "300", 1);
+    let result = relex(Lexer::new(&script), &old, edit, "300").unwrap();
+
+    assert_eq!(result.tokens, Lexer::new(&script).tokenize().unwrap());
+    assert!(result.relexed > 0);
+    assert!(
+        result.relexed < 10,
+        "expected only tokens near the edit to be lexed, got {} of {}",
+        result.relexed,
+        old.len()
+    );
+}
+
+#[test]
+pub fn relex_keeps_offsets_before_edit() {
+    let old = Lexer::new(SCRIPT).tokenize().unwrap();
+
+    let edit = 32..33;
+    let script = SCRIPT.replacen("4", "", 1);
+    let result = relex(Lexer::new(&script), &old, edit.clone(), "").unwrap();
+
+    let before = old.iter().take_while(|t| t.1.end < edit.start - 4);
+    for (old, new) in before.zip(result.tokens.iter()) {
+        assert_eq!(old.1, new.1);
+    }
+    assert_eq!(result.tokens, Lexer::new(&script).tokenize().unwrap());
+}
+
+#[test]
+pub fn relex_merging_tokens() {
+    let script = "$a = foo bar;";
+    let old = Lexer::new(script).tokenize().unwrap();
+
+    // removing the space merges `foo` and `bar` into a single identifier.
+    let new_script = "$a = foobar;";
+    let result = relex(Lexer::new(new_script), &old, 8..9, "").unwrap();
+
+    assert_eq!(result.tokens, Lexer::new(new_script).tokenize().unwrap());
+}
+
+#[test]
+pub fn relex_inserted_lines() {
+    let old = Lexer::new(SCRIPT).tokenize().unwrap();
+
+    // `$b = 2;` becomes `$b = 2\n\n;`
+    let script = SCRIPT.replacen("2;", "2\n\n;", 1);
+    let result = relex(Lexer::new(&script), &old, 14..14, "\n\n").unwrap();
+
+    assert_eq!(result.tokens, Lexer::new(&script).tokenize().unwrap());
+}
+
+#[test]
+pub fn relex_within_attribute() {
+    let script = "#[Foo(1, 2, 3, 4)]\nfunction foo() {}";
+    let old = Lexer::new(script).tokenize().unwrap();
+
+    // `4` becomes `40`, lexing again from within the attribute group.
+    let new_script = script.replacen("4", "40", 1);
+    let result = relex(Lexer::new(&new_script), &old, 15..16, "40").unwrap();
+
+    assert_eq!(result.tokens, Lexer::new(&new_script).tokenize().unwrap());
+    assert!(result
+        .tokens
+        .iter()
+        .any(|t| t.kind() == TokenType::AttributeEnd));
+}
+
+#[test]
+pub fn relex_keeps_lexer_options() {
+    let script = "<p>a</p>\n<?php $a = 1; ?>\n<p>b</p>\n";
+    let lexer = || Lexer::new(script).html_mode(HtmlMode::Opaque);
+    let old = lexer().tokenize().unwrap();
+
+    // `<p>b</p>` becomes `<p>bc</p>`, lexing again from after the close tag.
+    let new_script = script.replacen("b<", "bc<", 1);
+    let lexer = || Lexer::new(&new_script).html_mode(HtmlMode::Opaque);
+    let result = relex(lexer(), &old, 30..30, "c").unwrap();
+
+    assert_eq!(result.tokens, lexer().tokenize().unwrap());
+}
+
+#[test]
+pub fn token_ids_survive_edit() {
+    let old = Lexer::new(SCRIPT).tokenize().unwrap();
+
+    // `$c = 3;` becomes `$c = 300;`
+    let script = SCRIPT.replacen("3", "300", 1);
+    let result = relex(Lexer::new(&script), &old, 22..23, "300").unwrap();
+    let new = &result.tokens;
+
+    let old_ids = token_ids(&old);
+    let new_ids = result.ids(&old, &old_ids);
+    assert_eq!(old_ids.len(), new_ids.len());
+    let changed: Vec<usize> = (0..old_ids.len())
+        .filter(|&i| old_ids[i] != new_ids[i])
+        .collect();
+    assert_eq!(changed.len(), 1);
+    assert_eq!(new[changed[0]].value().as_deref(), Some("300"));
+}
+
+#[test]
+pub fn token_ids_survive_inserted_statement() {
+    let old = Lexer::new(SCRIPT).tokenize().unwrap();
+
+    // `$f = 6;\n` is inserted before `$b = 2;`, repeating tokens found throughout the script.
+    let script = SCRIPT.replacen("$b", "$f = 6;\n$b", 1);
+    let result = relex(Lexer::new(&script), &old, 8..8, "$f = 6;\n").unwrap();
+    assert_eq!(result.tokens, Lexer::new(&script).tokenize().unwrap());
+
+    let old_ids = token_ids(&old);
+    let new_ids = result.ids(&old, &old_ids);
+    assert_eq!(new_ids.len(), old_ids.len() + 8);
+    // every previous token keeps its identity, in the same order.
+    let kept: Vec<_> = new_ids.iter().filter(|id| old_ids.contains(id)).collect();
+    assert_eq!(kept, old_ids.iter().collect::<Vec<_>>());
+    for (i, id) in new_ids.iter().enumerate() {
+        assert!(
+            !new_ids[i + 1..].contains(id),
+            "{:?} is not unique",
+            result.tokens[i]
+        );
+    }
+}
+
+#[test]
+pub fn token_ids_are_unique() {
+    let tokens = Lexer::new(SCRIPT).tokenize().unwrap();
+    let ids = token_ids(&tokens);
+
+    for (i, id) in ids.iter().enumerate() {
+        assert!(!ids[i + 1..].contains(id), "{:?} is not unique", tokens[i]);
+    }
+    // the same tokens have the same identities, even when lexed at other offsets.
+    let shifted = Lexer::new(&format!("{}{}", SCRIPT, SCRIPT))
+        .tokenize()
+        .unwrap();
+    assert_eq!(token_ids(&shifted)[..ids.len()], ids);
+}
@@ -0,0 +1,94 @@
+use compiler::syntax::lex::{
+    interpolation::{string_parts, StringPart},
+    token::{StringType, TokenType},
+    Lexer,
+};
+
+/// The parts of the first token of the script, with the text of every expression token.
+fn parts(script: &str) -> Vec<Result<String, Vec<String>>> {
+    let tokens = Lexer::new(script).tokenize().unwrap();
+    assert_eq!(tokens.len(), 1, "{:?}", tokens);
+    string_parts(&tokens[0], script)
+        .unwrap()
+        .into_iter()
+        .map(|part| match part {
+            StringPart::Literal(text) => Ok(text),
+            StringPart::Expression(tokens) => Err(tokens
+                .iter()
+                .map(|t| t.source(script).to_string())
+                .collect()),
+        })
+        .collect()
+}
+
+fn expression(tokens: &[&str]) -> Result<String, Vec<String>> {
+    Err(tokens.iter().map(|t| t.to_string()).collect())
+}
+
+#[test]
+pub fn braced_array_access() {
+    assert_eq!(
+        parts(r#""{$a[$b]}""#),
+        vec![expression(&["$", "a", "[", "$", "b", "]"])]
+    );
+}
+
+#[test]
+pub fn brace_within_nested_string() {
+    let script = r#""x {$o->m('}')} y""#;
+    let tokens = Lexer::new(script).tokenize().unwrap();
+
+    assert_eq!(tokens[0].kind(), TokenType::StringLit(StringType::Double));
+    assert_eq!(tokens[0].value().unwrap(), "x {$o->m('}')} y");
+    assert_eq!(
+        parts(script),
+        vec![
+            Ok("x ".to_string()),
+            expression(&["$", "o", "->", "m", "(", "'}'", ")"]),
+            Ok(" y".to_string()),
+        ]
+    );
+}
+
+#[test]
+pub fn double_quote_within_interpolation() {
+    assert_eq!(
+        parts(r#""{$a["k"]}!""#),
+        vec![
+            expression(&["$", "a", "[", "\"k\"", "]"]),
+            Ok("!".to_string())
+        ]
+    );
+}
+
+#[test]
+pub fn nested_braces() {
+    assert_eq!(
+        parts(r#""{$a->{$b}}""#),
+        vec![expression(&["$", "a", "->", "{", "$", "b", "}"])]
+    );
+}
+
+#[test]
+pub fn plain_braces_are_not_interpolation() {
+    assert_eq!(
+        parts(r#""{not interpolation}""#),
+        vec![Ok("{not interpolation}".to_string())]
+    );
+}
+
+#[test]
+pub fn simple_variable() {
+    assert_eq!(
+        parts(r#""Hello $name\n""#),
+        vec![
+            Ok("Hello ".to_string()),
+            expression(&["$", "name"]),
+            Ok("\n".to_string()),
+        ]
+    );
+    assert_eq!(
+        parts(r#""\$name costs $5""#),
+        vec![Ok("$name costs $5".to_string())]
+    );
+}
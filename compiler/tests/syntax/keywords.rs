@@ -0,0 +1,840 @@
+use compiler::syntax::{
+    ast::keyword::{Keyword, KeywordSet},
+    lex::{
+        token::{AccessType, Numeric, StringType, TokenType},
+        version::PhpVersion,
+        warning::WarningCode,
+        Lexer,
+    },
+};
+
+use super::{kinds, lex, lex_text};
+
+#[test]
+pub fn global_declaration() {
+    assert_eq!(
+        kinds("global $x;"),
+        vec![
+            TokenType::Keyword(Keyword::Global),
+            TokenType::Variable,
+            TokenType::Identifier,
+            TokenType::EOS,
+        ]
+    );
+}
+
+#[test]
+pub fn static_declaration() {
+    let kinds = kinds("static $y = 1;");
+
+    assert_eq!(kinds[0], TokenType::Keyword(Keyword::Static));
+    assert_eq!(kinds[1], TokenType::Variable);
+    assert_eq!(kinds[2], TokenType::Identifier);
+    assert_eq!(kinds[3], TokenType::Operator);
+    assert!(matches!(kinds[4], TokenType::NumericalLit(_)));
+    assert_eq!(kinds[5], TokenType::EOS);
+}
+
+#[test]
+pub fn static_method_context() {
+    assert_eq!(
+        kinds("static::create();")[..2],
+        [
+            TokenType::Keyword(Keyword::Static),
+            TokenType::Accessor(AccessType::StaticMember),
+        ]
+    );
+    assert_eq!(
+        kinds("public static function")[..3],
+        [
+            TokenType::Keyword(Keyword::Public),
+            TokenType::Keyword(Keyword::Static),
+            TokenType::Keyword(Keyword::Function),
+        ]
+    );
+}
+
+#[test]
+pub fn keyword_prefixed_identifier() {
+    assert_eq!(kinds("globals statics"), vec![TokenType::Identifier; 2]);
+}
+
+#[test]
+pub fn elseif_single_keyword() {
+    assert_eq!(
+        kinds("} elseif ($a) {"),
+        vec![
+            TokenType::RightBrace,
+            TokenType::Keyword(Keyword::Elseif),
+            TokenType::LeftParenthesis,
+            TokenType::Variable,
+            TokenType::Identifier,
+            TokenType::RightParenthesis,
+            TokenType::LeftBrace,
+        ]
+    );
+    assert_eq!(kinds("ElseIf"), vec![TokenType::Keyword(Keyword::Elseif)]);
+}
+
+#[test]
+pub fn else_if_two_keywords() {
+    assert_eq!(
+        kinds("} else if ($a) {")[1..3],
+        [
+            TokenType::Keyword(Keyword::Else),
+            TokenType::Keyword(Keyword::If),
+        ]
+    );
+    assert_eq!(
+        kinds("else\n\tif"),
+        vec![
+            TokenType::Keyword(Keyword::Else),
+            TokenType::Keyword(Keyword::If),
+        ]
+    );
+}
+
+#[test]
+pub fn elseif_prefixed_identifier() {
+    assert_eq!(kinds("elseiffoo"), vec![TokenType::Identifier]);
+    assert_eq!(kinds("elsefoo"), vec![TokenType::Identifier]);
+    assert_eq!(lex("elseiffoo")[0].value().unwrap(), "elseiffoo");
+}
+
+#[test]
+pub fn use_plain() {
+    assert_eq!(
+        kinds("use Foo\\Bar;"),
+        vec![
+            TokenType::Keyword(Keyword::Use),
+            TokenType::Identifier,
+            TokenType::Backslash,
+            TokenType::Identifier,
+            TokenType::EOS,
+        ]
+    );
+}
+
+#[test]
+pub fn use_grouped() {
+    assert_eq!(
+        kinds("use Foo\\{Bar, Baz};"),
+        vec![
+            TokenType::Keyword(Keyword::Use),
+            TokenType::Identifier,
+            TokenType::Backslash,
+            TokenType::LeftBrace,
+            TokenType::Identifier,
+            TokenType::Comma,
+            TokenType::Identifier,
+            TokenType::RightBrace,
+            TokenType::EOS,
+        ]
+    );
+
+    // the group braces count towards the nesting depth.
+    let script = "use Foo\\{Bar};";
+    assert!(Lexer::new(script).max_nesting_depth(0).tokenize().is_err());
+    assert!(Lexer::new(script).max_nesting_depth(1).tokenize().is_ok());
+}
+
+#[test]
+pub fn use_closure() {
+    assert_eq!(
+        kinds("function () use ($captured) {}"),
+        vec![
+            TokenType::Keyword(Keyword::Function),
+            TokenType::LeftParenthesis,
+            TokenType::RightParenthesis,
+            TokenType::Keyword(Keyword::Use),
+            TokenType::LeftParenthesis,
+            TokenType::Variable,
+            TokenType::Identifier,
+            TokenType::RightParenthesis,
+            TokenType::LeftBrace,
+            TokenType::RightBrace,
+        ]
+    );
+}
+
+#[test]
+pub fn use_closure_trailing_comma() {
+    let mut lexer = Lexer::new("function () use ($a, $b,) {}")
+        .target(PhpVersion::PHP_7_4)
+        .warn_deprecated();
+    lexer.tokenize().unwrap();
+
+    assert_eq!(lexer.warnings()[0].code, WarningCode::TrailingComma);
+}
+
+#[test]
+pub fn backed_enum() {
+    assert_eq!(
+        lex_text("enum Suit: string { case Hearts = 'H'; }"),
+        vec![
+            (TokenType::Keyword(Keyword::Enum), "enum".to_string()),
+            (TokenType::Identifier, "Suit".to_string()),
+            (TokenType::Colon, ":".to_string()),
+            (TokenType::Identifier, "string".to_string()),
+            (TokenType::LeftBrace, "{".to_string()),
+            (TokenType::Keyword(Keyword::Case), "case".to_string()),
+            (TokenType::Identifier, "Hearts".to_string()),
+            (TokenType::Operator, "=".to_string()),
+            (TokenType::StringLit(StringType::Single), "H".to_string()),
+            (TokenType::EOS, ";".to_string()),
+            (TokenType::RightBrace, "}".to_string()),
+        ]
+    );
+}
+
+#[test]
+pub fn pure_enum() {
+    assert_eq!(
+        kinds("enum Status { case Active; case Inactive; }"),
+        vec![
+            TokenType::Keyword(Keyword::Enum),
+            TokenType::Identifier,
+            TokenType::LeftBrace,
+            TokenType::Keyword(Keyword::Case),
+            TokenType::Identifier,
+            TokenType::EOS,
+            TokenType::Keyword(Keyword::Case),
+            TokenType::Identifier,
+            TokenType::EOS,
+            TokenType::RightBrace,
+        ]
+    );
+}
+
+#[test]
+pub fn declare_directive() {
+    assert_eq!(
+        lex_text("declare(strict_types=1);"),
+        vec![
+            (TokenType::Keyword(Keyword::Declare), "declare".to_string()),
+            (TokenType::LeftParenthesis, "(".to_string()),
+            (TokenType::Identifier, "strict_types".to_string()),
+            (TokenType::Operator, "=".to_string()),
+            (TokenType::NumericalLit(Numeric::Int(1)), "1".to_string()),
+            (TokenType::RightParenthesis, ")".to_string()),
+            (TokenType::EOS, ";".to_string()),
+        ]
+    );
+}
+
+#[test]
+pub fn declare_directives_are_identifiers() {
+    let kinds = kinds("declare(ticks=1, encoding='UTF-8');");
+
+    assert_eq!(kinds[2], TokenType::Identifier);
+    assert_eq!(kinds[6], TokenType::Identifier);
+    assert_eq!(kinds[7], TokenType::Operator);
+    assert_eq!(kinds[8], TokenType::StringLit(StringType::Single));
+}
+
+#[test]
+pub fn method_modifiers() {
+    assert_eq!(
+        kinds("public static function"),
+        vec![
+            TokenType::Keyword(Keyword::Public),
+            TokenType::Keyword(Keyword::Static),
+            TokenType::Keyword(Keyword::Function),
+        ]
+    );
+    assert_eq!(
+        kinds("final protected function"),
+        vec![
+            TokenType::Keyword(Keyword::Final),
+            TokenType::Keyword(Keyword::Protected),
+            TokenType::Keyword(Keyword::Function),
+        ]
+    );
+}
+
+#[test]
+pub fn abstract_class() {
+    assert_eq!(
+        kinds("abstract class Shape {}"),
+        vec![
+            TokenType::Keyword(Keyword::Abstract),
+            TokenType::Keyword(Keyword::Class),
+            TokenType::Identifier,
+            TokenType::LeftBrace,
+            TokenType::RightBrace,
+        ]
+    );
+}
+
+#[test]
+pub fn readonly_property() {
+    assert_eq!(
+        lex_text("private readonly int $id;"),
+        vec![
+            (TokenType::Keyword(Keyword::Private), "private".to_string()),
+            (
+                TokenType::Keyword(Keyword::ReadOnly),
+                "readonly".to_string()
+            ),
+            (TokenType::Identifier, "int".to_string()),
+            (TokenType::Variable, "$".to_string()),
+            (TokenType::Identifier, "id".to_string()),
+            (TokenType::EOS, ";".to_string()),
+        ]
+    );
+}
+
+#[test]
+pub fn modifier_prefixes_are_identifiers() {
+    assert_eq!(
+        kinds("publication finalize statics"),
+        vec![
+            TokenType::Identifier,
+            TokenType::Identifier,
+            TokenType::Identifier
+        ]
+    );
+}
+
+#[test]
+pub fn new_instance() {
+    assert_eq!(
+        kinds("new Foo();"),
+        vec![
+            TokenType::Keyword(Keyword::New),
+            TokenType::Identifier,
+            TokenType::LeftParenthesis,
+            TokenType::RightParenthesis,
+            TokenType::EOS,
+        ]
+    );
+}
+
+#[test]
+pub fn new_anonymous_class() {
+    assert_eq!(
+        kinds("new class ($arg) extends Base { }"),
+        vec![
+            TokenType::Keyword(Keyword::New),
+            TokenType::Keyword(Keyword::Class),
+            TokenType::LeftParenthesis,
+            TokenType::Variable,
+            TokenType::Identifier,
+            TokenType::RightParenthesis,
+            TokenType::Keyword(Keyword::Extends),
+            TokenType::Identifier,
+            TokenType::LeftBrace,
+            TokenType::RightBrace,
+        ]
+    );
+}
+
+#[test]
+pub fn throw_statement() {
+    assert_eq!(
+        kinds("throw new E();"),
+        vec![
+            TokenType::Keyword(Keyword::Throw),
+            TokenType::Keyword(Keyword::New),
+            TokenType::Identifier,
+            TokenType::LeftParenthesis,
+            TokenType::RightParenthesis,
+            TokenType::EOS,
+        ]
+    );
+}
+
+#[test]
+pub fn throw_expression() {
+    let kinds = kinds("$x = $cond ? 1 : throw new Exception();");
+
+    assert_eq!(kinds[4], TokenType::Identifier);
+    assert_eq!(kinds[5], TokenType::QuestionMark);
+    assert!(matches!(kinds[6], TokenType::NumericalLit(_)));
+    assert_eq!(kinds[7], TokenType::Colon);
+    assert_eq!(kinds[8], TokenType::Keyword(Keyword::Throw));
+    assert_eq!(kinds[9], TokenType::Keyword(Keyword::New));
+}
+
+#[test]
+pub fn match_arms() {
+    assert_eq!(
+        lex_text("match ($x) { 1, 2 => 'a', default => 'b' }"),
+        vec![
+            (TokenType::Keyword(Keyword::Match), "match".to_string()),
+            (TokenType::LeftParenthesis, "(".to_string()),
+            (TokenType::Variable, "$".to_string()),
+            (TokenType::Identifier, "x".to_string()),
+            (TokenType::RightParenthesis, ")".to_string()),
+            (TokenType::LeftBrace, "{".to_string()),
+            (TokenType::NumericalLit(Numeric::Int(1)), "1".to_string()),
+            (TokenType::Comma, ",".to_string()),
+            (TokenType::NumericalLit(Numeric::Int(2)), "2".to_string()),
+            (TokenType::Operator, "=>".to_string()),
+            (TokenType::StringLit(StringType::Single), "a".to_string()),
+            (TokenType::Comma, ",".to_string()),
+            (TokenType::Keyword(Keyword::Default), "default".to_string()),
+            (TokenType::Operator, "=>".to_string()),
+            (TokenType::StringLit(StringType::Single), "b".to_string()),
+            (TokenType::RightBrace, "}".to_string()),
+        ]
+    );
+}
+
+#[test]
+pub fn arrow_function() {
+    let tokens = lex_text("fn($x) => $x * 2");
+
+    assert_eq!(
+        tokens[0],
+        (TokenType::Keyword(Keyword::Fn), "fn".to_string())
+    );
+    assert_eq!(tokens[1].0, TokenType::LeftParenthesis);
+    assert_eq!(tokens[4].0, TokenType::RightParenthesis);
+    assert_eq!(tokens[5], (TokenType::Operator, "=>".to_string()));
+    assert_eq!(tokens[8], (TokenType::Operator, "*".to_string()));
+    assert!(matches!(tokens[9].0, TokenType::NumericalLit(_)));
+    // variables are captured implicitly, there is no `use`.
+    assert!(tokens
+        .iter()
+        .all(|t| t.0 != TokenType::Keyword(Keyword::Use)));
+}
+
+#[test]
+pub fn nested_arrow_function() {
+    let tokens = kinds("fn($x) => fn($y) => $x + $y");
+    let arrows = tokens
+        .iter()
+        .filter(|t| **t == TokenType::Keyword(Keyword::Fn))
+        .count();
+
+    assert_eq!(arrows, 2);
+    assert_eq!(tokens[5], TokenType::Operator);
+    assert_eq!(tokens[6], TokenType::Keyword(Keyword::Fn));
+    assert_eq!(tokens[11], TokenType::Operator);
+}
+
+#[test]
+pub fn contextual_keywords_as_names() {
+    for name in ["match", "enum", "readonly", "from"] {
+        let tokens = lex(&format!("function {}() {{}}", name));
+
+        assert_eq!(tokens[1].text().as_deref(), Some(name));
+        assert!(matches!(tokens[1].kind(), TokenType::Keyword(_)));
+        assert!(tokens[1].could_be_identifier(), "{}", name);
+    }
+}
+
+#[test]
+pub fn contextual_keywords_in_use() {
+    let tokens = lex("yield from $gen; $a->match;");
+
+    assert_eq!(tokens[1].kind(), TokenType::Keyword(Keyword::From));
+    assert!(tokens[1].could_be_identifier());
+    assert_eq!(tokens[8].kind(), TokenType::Keyword(Keyword::Match));
+    assert!(tokens[8].could_be_identifier());
+}
+
+#[test]
+pub fn reserved_keywords_are_not_names() {
+    let tokens = lex("function class() {}");
+
+    assert!(!tokens[0].could_be_identifier());
+    assert!(!tokens[1].could_be_identifier());
+    assert!(!lex("foo")[0].could_be_identifier());
+}
+
+#[test]
+pub fn switch_statement() {
+    let kinds = kinds(
+        "switch ($i) {\n    case 0:\n        continue;\n    case 1:\n        break 2;\n    default:\n        break;\n}",
+    );
+
+    assert_eq!(
+        kinds,
+        vec![
+            TokenType::Keyword(Keyword::Switch),
+            TokenType::LeftParenthesis,
+            TokenType::Variable,
+            TokenType::Identifier,
+            TokenType::RightParenthesis,
+            TokenType::LeftBrace,
+            TokenType::Keyword(Keyword::Case),
+            TokenType::NumericalLit(Numeric::Int(0)),
+            TokenType::Colon,
+            TokenType::Keyword(Keyword::Continue),
+            TokenType::EOS,
+            TokenType::Keyword(Keyword::Case),
+            TokenType::NumericalLit(Numeric::Int(1)),
+            TokenType::Colon,
+            TokenType::Keyword(Keyword::Break),
+            TokenType::NumericalLit(Numeric::Int(2)),
+            TokenType::EOS,
+            TokenType::Keyword(Keyword::Default),
+            TokenType::Colon,
+            TokenType::Keyword(Keyword::Break),
+            TokenType::EOS,
+            TokenType::RightBrace,
+        ]
+    );
+}
+
+#[test]
+pub fn keywords_are_case_insensitive() {
+    let tokens = lex("FUNCTION Foo() { Return TRUE; }");
+
+    assert_eq!(tokens[0].kind(), TokenType::Keyword(Keyword::Function));
+    assert_eq!(tokens[0].value().as_deref(), Some("FUNCTION"));
+    assert_eq!(tokens[5].kind(), TokenType::Keyword(Keyword::Return));
+    assert_eq!(tokens[6].kind(), TokenType::Boolean);
+    assert_eq!(tokens[6].value().as_deref(), Some("TRUE"));
+}
+
+#[test]
+pub fn normalize_keywords() {
+    let script = "FUNCTION f() { return False; }";
+    let tokens: Vec<_> = Lexer::new(script)
+        .normalize_keywords()
+        .tokenize()
+        .unwrap()
+        .into_iter()
+        .filter(|t| t.kind() != TokenType::Whitespace)
+        .collect();
+
+    assert_eq!(tokens[0].value().as_deref(), Some("function"));
+    assert_eq!(tokens[0].source(script), "FUNCTION");
+    assert_eq!(tokens[6].kind(), TokenType::Boolean);
+    assert_eq!(tokens[6].value().as_deref(), Some("false"));
+    assert_eq!(tokens[6].source(script), "False");
+    // identifiers keep their casing.
+    assert_eq!(tokens[1].value().as_deref(), Some("f"));
+}
+
+#[test]
+pub fn boolean_prefixes_are_identifiers() {
+    assert_eq!(
+        kinds("trueish falsey false"),
+        vec![
+            TokenType::Identifier,
+            TokenType::Identifier,
+            TokenType::Boolean
+        ]
+    );
+}
+
+#[test]
+pub fn literal_keyword_casings() {
+    for script in ["true", "TRUE", "True", "false", "FALSE", "fAlSe"] {
+        assert_eq!(kinds(script), vec![TokenType::Boolean], "{}", script);
+    }
+    for script in ["null", "NULL", "Null"] {
+        assert_eq!(kinds(script), vec![TokenType::Null], "{}", script);
+        assert_eq!(lex(script)[0].value().as_deref(), Some(script));
+    }
+}
+
+#[test]
+pub fn null_literal_in_expression() {
+    assert_eq!(
+        kinds("$a ?? NULL;"),
+        vec![
+            TokenType::Variable,
+            TokenType::Identifier,
+            TokenType::Operator,
+            TokenType::Null,
+            TokenType::EOS,
+        ]
+    );
+    let tokens = Lexer::new("Null").normalize_keywords().tokenize().unwrap();
+    assert_eq!(tokens[0].value().as_deref(), Some("null"));
+}
+
+#[test]
+pub fn null_prefixes_are_identifiers() {
+    assert_eq!(
+        kinds("nullable nulls null_value nul"),
+        vec![TokenType::Identifier; 4]
+    );
+}
+
+#[test]
+pub fn custom_keyword_set() {
+    let keywords = KeywordSet::new()
+        .with("wait", Keyword::Await)
+        .without("match");
+    let tokens = Lexer::new("wait match($x);")
+        .keywords(keywords)
+        .tokenize()
+        .unwrap();
+    let kinds: Vec<TokenType> = tokens
+        .iter()
+        .map(|t| t.kind())
+        .filter(|k| !matches!(k, TokenType::Whitespace))
+        .collect();
+
+    assert_eq!(
+        kinds,
+        vec![
+            TokenType::Keyword(Keyword::Await),
+            TokenType::Identifier,
+            TokenType::LeftParenthesis,
+            TokenType::Variable,
+            TokenType::Identifier,
+            TokenType::RightParenthesis,
+            TokenType::EOS,
+        ]
+    );
+    assert_eq!(tokens[0].value(), Some("wait".to_string()));
+}
+
+#[test]
+pub fn custom_keyword_longer_than_builtin() {
+    let keywords = KeywordSet::new().with("asynchronously", Keyword::Async);
+
+    assert_eq!(keywords.get("ASYNCHRONOUSLY"), Some(Keyword::Async));
+    assert_eq!(
+        Lexer::new("asynchronously")
+            .keywords(keywords)
+            .tokenize()
+            .unwrap()[0]
+            .kind(),
+        TokenType::Keyword(Keyword::Async)
+    );
+    assert_eq!(kinds("asynchronously"), vec![TokenType::Identifier]);
+}
+
+#[test]
+pub fn custom_keyword_with_multibyte_chars() {
+    let keywords = KeywordSet::new().with("función", Keyword::Function);
+    let tokens = Lexer::new("función foo")
+        .keywords(keywords)
+        .tokenize()
+        .unwrap();
+
+    assert_eq!(tokens[0].kind(), TokenType::Keyword(Keyword::Function));
+    assert_eq!(tokens[0].value(), Some("función".to_string()));
+    assert_eq!(tokens[1].kind(), TokenType::Whitespace);
+    assert_eq!(tokens[2].kind(), TokenType::Identifier);
+    assert_eq!(tokens[2].value(), Some("foo".to_string()));
+}
+
+#[test]
+#[should_panic(expected = "keyword \"\" is not an identifier")]
+pub fn custom_keyword_must_not_be_empty() {
+    let _ = KeywordSet::default().with("", Keyword::Function);
+}
+
+#[test]
+pub fn custom_keyword_must_be_identifier() {
+    for word in ["1fn", "my-fn", "fn ", "$fn"] {
+        let result = std::panic::catch_unwind(|| KeywordSet::new().without(word));
+        assert!(result.is_err(), "{:?} should be rejected", word);
+    }
+}
+
+#[test]
+pub fn keyword_set_lookup() {
+    let keywords = KeywordSet::new().with("Función", Keyword::Function);
+
+    // as in PHP, only ASCII letters are case insensitive.
+    assert_eq!(keywords.get("FUNCIÓN"), None);
+    assert_eq!(keywords.get("funciÓn"), None);
+    assert_eq!(keywords.get("FUNCIóN"), Some(Keyword::Function));
+    assert_eq!(keywords.get("Class"), Some(Keyword::Class));
+    assert_eq!(keywords.max_len(), 11);
+    assert_eq!(
+        KeywordSet::new()
+            .with("asynchronously", Keyword::Async)
+            .max_len(),
+        14
+    );
+    assert_eq!(
+        KeywordSet::new()
+            .with("asynchronously", Keyword::Async)
+            .without("asynchronously")
+            .max_len(),
+        11
+    );
+}
+
+fn hook_kinds(script: &str, version: PhpVersion) -> Vec<TokenType> {
+    Lexer::new(script)
+        .target(version)
+        .tokenize()
+        .unwrap()
+        .iter()
+        .map(|t| t.kind())
+        .filter(|k| !matches!(k, TokenType::Whitespace))
+        .collect()
+}
+
+#[test]
+pub fn property_hooks() {
+    let script = "public int $x { get => $this->x; set { $this->get(); } }";
+    let kinds = hook_kinds(script, PhpVersion::PHP_8_4);
+
+    assert_eq!(kinds[5], TokenType::Keyword(Keyword::Get));
+    assert_eq!(kinds[12], TokenType::Keyword(Keyword::Set));
+    // `get` called within the body of a hook is a method name.
+    assert_eq!(kinds[17], TokenType::Identifier);
+}
+
+#[test]
+pub fn property_hooks_before_8_4() {
+    let kinds = hook_kinds("public int $x { get => 1; }", PhpVersion::PHP_8_3);
+
+    assert_eq!(kinds[5], TokenType::Identifier);
+}
+
+#[test]
+pub fn get_and_set_methods() {
+    let kinds = hook_kinds("function get() {} $map->set($k, $v);", PhpVersion::PHP_8_4);
+
+    assert_eq!(kinds[1], TokenType::Identifier);
+    assert_eq!(kinds[9], TokenType::Identifier);
+    assert!(!kinds.contains(&TokenType::Keyword(Keyword::Get)));
+    assert!(!kinds.contains(&TokenType::Keyword(Keyword::Set)));
+}
+
+#[test]
+pub fn print_construct() {
+    assert_eq!(
+        lex_text("print \"hi\";"),
+        vec![
+            (TokenType::Keyword(Keyword::Print), "print".to_string()),
+            (TokenType::StringLit(StringType::Double), "hi".to_string()),
+            (TokenType::EOS, ";".to_string()),
+        ]
+    );
+    assert_eq!(kinds("PRINT($a);")[0], TokenType::Keyword(Keyword::Print));
+}
+
+#[test]
+pub fn print_as_name() {
+    assert_eq!(
+        lex_text("printf('%d', 1);")[..2],
+        [
+            (TokenType::Identifier, "printf".to_string()),
+            (TokenType::LeftParenthesis, "(".to_string()),
+        ]
+    );
+    for script in ["$print = 1;", "$class = 1;", "$$null = 1;"] {
+        let kinds = kinds(script);
+        assert_eq!(kinds[kinds.len() - 5], TokenType::Variable, "{}", script);
+        assert_eq!(kinds[kinds.len() - 4], TokenType::Identifier, "{}", script);
+    }
+}
+
+#[test]
+pub fn const_declaration() {
+    assert_eq!(
+        lex_text("const FOO = 1;"),
+        vec![
+            (TokenType::Keyword(Keyword::Const), "const".to_string()),
+            (TokenType::Identifier, "FOO".to_string()),
+            (TokenType::Operator, "=".to_string()),
+            (TokenType::NumericalLit(Numeric::Int(1)), "1".to_string()),
+            (TokenType::EOS, ";".to_string()),
+        ]
+    );
+    assert_eq!(
+        kinds("class A { public const B = 2; }")[3..5],
+        [
+            TokenType::Keyword(Keyword::Public),
+            TokenType::Keyword(Keyword::Const),
+        ]
+    );
+}
+
+#[test]
+pub fn define_call() {
+    assert_eq!(
+        lex_text("define('FOO', 1);")[..4],
+        [
+            (TokenType::Identifier, "define".to_string()),
+            (TokenType::LeftParenthesis, "(".to_string()),
+            (TokenType::StringLit(StringType::Single), "FOO".to_string()),
+            (TokenType::Comma, ",".to_string()),
+        ]
+    );
+    assert_eq!(kinds("defined('FOO')")[0], TokenType::Identifier);
+    assert_eq!(kinds("constant('FOO')")[0], TokenType::Identifier);
+}
+
+/// The conditions of each arm of the `match` opening the script, the text of their tokens
+/// joined without whitespace. An arm ends at the `,` following its result.
+fn match_conditions(script: &str) -> Vec<String> {
+    let mut conditions = Vec::new();
+    let mut condition = Some(String::new());
+    let mut depth = 0;
+    for (kind, text) in lex_text(script) {
+        match kind {
+            TokenType::LeftParenthesis | TokenType::LeftBracket | TokenType::LeftBrace => {
+                depth += 1
+            }
+            TokenType::RightParenthesis | TokenType::RightBracket | TokenType::RightBrace => {
+                depth -= 1
+            }
+            _ => {}
+        }
+        match (kind, text.as_str(), &mut condition) {
+            (TokenType::LeftBrace, _, Some(condition)) if depth == 1 => condition.clear(),
+            (TokenType::Operator, "=>", condition @ Some(_)) if depth == 1 => {
+                conditions.extend(condition.take())
+            }
+            (TokenType::Comma, _, None) if depth == 1 => condition = Some(String::new()),
+            (_, text, Some(condition)) => condition.push_str(text),
+            _ => {}
+        }
+    }
+    conditions
+}
+
+#[test]
+pub fn match_true_conditions() {
+    let script = "match (true) {
+        $x > 5, $x <= -1 => 'far',
+        is_int($x) && $x >= 0 => 'near',
+        $x instanceof Foo, in_array($x, [1, 2]) => 'other',
+        $x?->y === strlen('=>') => 'nullsafe',
+        default => 'none',
+    }";
+
+    assert_eq!(
+        match_conditions(script),
+        vec![
+            "$x>5,$x<=-1",
+            "is_int($x)&&$x>=0",
+            "$xinstanceofFoo,in_array($x,[1,2])",
+            "$x?->y===strlen(=>)",
+            "default",
+        ]
+    );
+    let tokens = lex(script);
+    assert_eq!(tokens[0].kind(), TokenType::Keyword(Keyword::Match));
+    assert_eq!(tokens[2].kind(), TokenType::Boolean);
+}
+
+#[test]
+pub fn reserved_and_contextual() {
+    assert!(Keyword::Class.is_reserved());
+    assert!(!Keyword::Class.is_contextual());
+    assert!(Keyword::Enum.is_contextual());
+    assert!(Keyword::ReadOnly.is_contextual());
+    assert!(!Keyword::ReadOnly.is_reserved());
+    assert!(Keyword::Function.is_reserved() && Keyword::Print.is_reserved());
+
+    for word in ["class", "enum", "readonly", "match", "from", "static"] {
+        let token = &lex(word)[0];
+        let TokenType::Keyword(keyword) = token.kind() else {
+            panic!("{} should lex as a keyword", word);
+        };
+        assert_eq!(
+            keyword.is_contextual(),
+            token.could_be_identifier(),
+            "{}",
+            word
+        );
+        assert_ne!(keyword.is_contextual(), keyword.is_reserved(), "{}", word);
+    }
+}
@@ -1,6 +1,36 @@
-use compiler::syntax::lex::Lexer;
+use compiler::syntax::lex::{
+    token::{AccessType, Token, TokenType},
+    Lexer,
+};
 
-const TEST_SCRIPT: &'static str = r#"
+mod analysis;
+mod attributes;
+mod cache;
+mod comments;
+mod conformance;
+mod destructuring;
+mod docblock;
+mod errors;
+mod format;
+mod heredoc;
+mod hint;
+mod identifiers;
+mod incremental;
+mod interpolation;
+mod keywords;
+mod numbers;
+mod operators;
+mod recovery;
+mod span;
+mod stream;
+mod strings;
+mod tags;
+mod test_utils;
+mod token;
+mod warnings;
+mod whitespace;
+
+const TEST_SCRIPT: &str = r#"
 <?php
 use rsphp\Mixed;
 
@@ -28,7 +58,7 @@ pub fn intitial_lex() {
         let token = lexer.next();
         if let Ok(x) = token {
             if x.is_none() {
-                println!("EOF???");
+                break;
             }
             println!("{:?}", x);
         } else {
@@ -37,3 +67,34 @@ pub fn intitial_lex() {
         }
     }
 }
+
+/// Lexes the script, dropping any whitespace tokens.
+pub fn lex(script: &str) -> Vec<Token> {
+    Lexer::new(script)
+        .tokenize()
+        .unwrap()
+        .into_iter()
+        .filter(|t| !matches!(t.kind(), TokenType::Whitespace))
+        .collect()
+}
+
+/// Lexes the script into the kind and text of each token, dropping any whitespace.
+pub fn lex_text(script: &str) -> Vec<(TokenType, String)> {
+    lex(script)
+        .into_iter()
+        .map(|t| (t.kind(), t.text().unwrap_or_default().into_owned()))
+        .collect()
+}
+
+/// Lexes the script into the kind of each token, dropping any whitespace.
+pub fn kinds(script: &str) -> Vec<TokenType> {
+    lex(script).iter().map(|t| t.kind()).collect()
+}
+
+/// The kind and text of the `->` token, as given by `lex_text`.
+pub fn arrow() -> (TokenType, String) {
+    (
+        TokenType::Accessor(AccessType::ReferenceMember),
+        "->".to_string(),
+    )
+}
@@ -0,0 +1,367 @@
+use compiler::syntax::lex::{
+    error::LexError,
+    token::{Numeric, TokenType},
+    Lexer,
+};
+use proptest::prelude::*;
+
+use super::{arrow, lex, lex_text};
+
+#[test]
+pub fn integers_and_floats() {
+    let tokens = lex("1 1.5 2147483648");
+
+    assert_eq!(tokens[0].kind(), TokenType::NumericalLit(Numeric::Int(1)));
+    assert_eq!(
+        tokens[1].kind(),
+        TokenType::NumericalLit(Numeric::Float(1.5))
+    );
+    assert_eq!(
+        tokens[2].kind(),
+        TokenType::NumericalLit(Numeric::LInt(2147483648))
+    );
+}
+
+#[test]
+pub fn concatenation_before_digit() {
+    assert_eq!(
+        lex_text("$a.5"),
+        vec![
+            (TokenType::Variable, "$".to_string()),
+            (TokenType::Identifier, "a".to_string()),
+            (TokenType::Dot, ".".to_string()),
+            (TokenType::NumericalLit(Numeric::Int(5)), "5".to_string()),
+        ]
+    );
+}
+
+#[test]
+pub fn spaced_concatenation() {
+    assert_eq!(
+        lex_text("$a . 5"),
+        vec![
+            (TokenType::Variable, "$".to_string()),
+            (TokenType::Identifier, "a".to_string()),
+            (TokenType::Dot, ".".to_string()),
+            (TokenType::NumericalLit(Numeric::Int(5)), "5".to_string()),
+        ]
+    );
+}
+
+#[test]
+pub fn leading_dot_float() {
+    assert_eq!(
+        lex_text("$x = .5"),
+        vec![
+            (TokenType::Variable, "$".to_string()),
+            (TokenType::Identifier, "x".to_string()),
+            (TokenType::Operator, "=".to_string()),
+            (
+                TokenType::NumericalLit(Numeric::Float(0.5)),
+                ".5".to_string()
+            ),
+        ]
+    );
+}
+
+#[test]
+pub fn int_before_arrow() {
+    assert_eq!(
+        lex_text("5->foo()"),
+        vec![
+            (TokenType::NumericalLit(Numeric::Int(5)), "5".to_string()),
+            arrow(),
+            (TokenType::Identifier, "foo".to_string()),
+            (TokenType::LeftParenthesis, "(".to_string()),
+            (TokenType::RightParenthesis, ")".to_string()),
+        ]
+    );
+}
+
+#[test]
+pub fn float_before_arrow() {
+    for (script, value) in [("5.0->foo()", 5.0), ("5.2->foo", 5.2)] {
+        let tokens = lex_text(script);
+
+        assert_eq!(
+            tokens[0],
+            (
+                TokenType::NumericalLit(Numeric::Float(value)),
+                script[..3].to_string()
+            )
+        );
+        assert_eq!(tokens[1], arrow());
+        assert_eq!(tokens[2], (TokenType::Identifier, "foo".to_string()));
+    }
+}
+
+#[test]
+pub fn parenthesized_int_before_arrow() {
+    assert_eq!(
+        lex_text("(5)->bar()")[..4],
+        [
+            (TokenType::LeftParenthesis, "(".to_string()),
+            (TokenType::NumericalLit(Numeric::Int(5)), "5".to_string()),
+            (TokenType::RightParenthesis, ")".to_string()),
+            arrow(),
+        ]
+    );
+}
+
+#[test]
+pub fn prefixed_integers() {
+    let tokens = lex("0x1F 0XfF 0b101 0B11 0o17 0O7");
+    let values: Vec<_> = tokens.iter().map(|t| t.kind()).collect();
+
+    assert_eq!(
+        values,
+        [31, 255, 5, 3, 15, 7].map(|i| TokenType::NumericalLit(Numeric::Int(i)))
+    );
+    assert_eq!(tokens[0].value().as_deref(), Some("0x1F"));
+}
+
+#[test]
+pub fn leading_zero() {
+    let tokens = lex("0 0755 0.5 00 0_17 0755.5 09e1");
+    let kinds: Vec<_> = tokens.iter().map(|t| t.kind()).collect();
+
+    assert_eq!(
+        kinds,
+        [
+            Numeric::Int(0),
+            Numeric::Int(0o755),
+            Numeric::Float(0.5),
+            Numeric::Int(0),
+            Numeric::Int(0o17),
+            Numeric::Float(755.5),
+            Numeric::Float(90.0),
+        ]
+        .map(TokenType::NumericalLit)
+    );
+    assert_eq!(tokens[1].value().as_deref(), Some("0755"));
+}
+
+#[test]
+pub fn leading_zero_invalid_octal() {
+    for script in ["08", "0758"] {
+        let error = Lexer::new(script).tokenize().unwrap_err();
+
+        assert_eq!(
+            LexError::from_io(&error),
+            Some(&LexError::InvalidNumber {
+                span: 0..script.len()
+            })
+        );
+    }
+}
+
+#[test]
+pub fn leading_zero_lenient() {
+    let tokens = Lexer::new("08 0755").lenient_octal().tokenize().unwrap();
+
+    assert_eq!(tokens[0].kind(), TokenType::NumericalLit(Numeric::Int(8)));
+    assert_eq!(
+        tokens[2].kind(),
+        TokenType::NumericalLit(Numeric::Int(0o755))
+    );
+}
+
+#[test]
+pub fn separators_and_exponents() {
+    let tokens = lex("1_000_000 0x7f_ff 1e3 2.5E-2 1_0.0_1e+1_0");
+
+    assert_eq!(
+        tokens[0].kind(),
+        TokenType::NumericalLit(Numeric::Int(1000000))
+    );
+    assert_eq!(tokens[0].value().as_deref(), Some("1_000_000"));
+    assert_eq!(
+        tokens[1].kind(),
+        TokenType::NumericalLit(Numeric::Int(0x7fff))
+    );
+    assert_eq!(
+        tokens[2].kind(),
+        TokenType::NumericalLit(Numeric::Float(1e3))
+    );
+    assert_eq!(
+        tokens[3].kind(),
+        TokenType::NumericalLit(Numeric::Float(2.5e-2))
+    );
+    assert_eq!(
+        tokens[4].kind(),
+        TokenType::NumericalLit(Numeric::Float(10.01e10))
+    );
+}
+
+#[test]
+pub fn prefixed_overflow() {
+    let tokens = lex("0xFFFFFFFF 0x1FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF");
+
+    assert_eq!(
+        tokens[0].kind(),
+        TokenType::NumericalLit(Numeric::LInt(0xFFFFFFFF))
+    );
+    assert_eq!(
+        tokens[1].kind(),
+        TokenType::NumericalLit(Numeric::Float(2f64.powi(129) - 1.0))
+    );
+}
+
+/// How a generated literal is written, alongside the value it should lex to.
+#[derive(Debug, Clone)]
+struct Literal {
+    text: String,
+    value: Numeric,
+}
+
+/// Writes the digits, placing an underscore before each digit whose flag is set.
+/// An underscore is never placed before the first digit.
+fn separate(digits: &str, separators: &[bool]) -> String {
+    let mut text = String::new();
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && separators.get(i).copied().unwrap_or_default() {
+            text.push('_');
+        }
+        text.push(digit);
+    }
+    text
+}
+
+fn int_value(value: u64) -> Numeric {
+    match i32::try_from(value) {
+        Ok(i) => Numeric::Int(i),
+        Err(_) => Numeric::LInt(value as i128),
+    }
+}
+
+fn separators() -> impl Strategy<Value = Vec<bool>> {
+    prop::collection::vec(prop::bool::weighted(0.2), 0..24)
+}
+
+fn decimal() -> impl Strategy<Value = Literal> {
+    (any::<u64>(), separators()).prop_map(|(value, separators)| Literal {
+        text: separate(&value.to_string(), &separators),
+        value: int_value(value),
+    })
+}
+
+fn prefixed() -> impl Strategy<Value = Literal> {
+    let prefix = prop_oneof![
+        Just("0x"),
+        Just("0X"),
+        Just("0b"),
+        Just("0B"),
+        Just("0o"),
+        Just("0O"),
+        Just("0"),
+    ];
+    (prefix, any::<u64>(), separators()).prop_map(|(prefix, value, separators)| {
+        let digits = match prefix {
+            "0x" | "0X" => format!("{:x}", value),
+            "0b" | "0B" => format!("{:b}", value),
+            _ => format!("{:o}", value),
+        };
+        Literal {
+            text: format!("{}{}", prefix, separate(&digits, &separators)),
+            value: int_value(value),
+        }
+    })
+}
+
+fn float() -> impl Strategy<Value = Literal> {
+    (
+        0u32..1_000_000,
+        prop::option::of("[0-9]{1,6}"),
+        prop::option::of((prop_oneof![Just(""), Just("+"), Just("-")], 0u32..300)),
+        separators(),
+    )
+        .prop_filter(
+            "a float needs a fraction or an exponent",
+            |(_, fraction, exponent, _)| fraction.is_some() || exponent.is_some(),
+        )
+        .prop_map(|(int, fraction, exponent, separators)| {
+            let mut text = separate(&int.to_string(), &separators);
+            let mut plain = int.to_string();
+            if let Some(fraction) = &fraction {
+                text.push('.');
+                text.push_str(&separate(fraction, &separators));
+                plain = format!("{}.{}", plain, fraction);
+            }
+            if let Some((sign, exponent)) = exponent {
+                text.push_str(&format!("e{}{}", sign, exponent));
+                plain = format!("{}e{}{}", plain, sign, exponent);
+            }
+            Literal {
+                text,
+                value: Numeric::Float(plain.parse().unwrap()),
+            }
+        })
+}
+
+/// A literal that is one mistake away from being valid.
+fn invalid() -> impl Strategy<Value = String> {
+    let literal = prop_oneof![decimal(), prefixed(), float()].prop_map(|literal| literal.text);
+    prop_oneof![
+        literal.clone().prop_map(|text| format!("{}_", text)),
+        (literal.clone(), any::<prop::sample::Index>()).prop_map(|(text, index)| {
+            let digits: Vec<_> = text.match_indices(|c: char| c.is_ascii_digit()).collect();
+            let (at, _) = digits[index.index(digits.len())];
+            format!("{}__{}", &text[..=at], &text[at + 1..])
+        }),
+        prop_oneof![
+            Just("0x"),
+            Just("0b"),
+            Just("0o"),
+            Just("0x_1"),
+            Just("0b2"),
+            Just("0o8")
+        ]
+        .prop_map(str::to_string),
+        (0u32..1000, prop_oneof![Just("e"), Just("e+"), Just("E-")])
+            .prop_map(|(int, exponent)| format!("{}{}", int, exponent)),
+        (decimal(), "[g-zG-Z]").prop_map(|(literal, suffix)| literal.text + &suffix),
+    ]
+}
+
+proptest! {
+    #[test]
+    fn numeric_literals_round_trip(literal in prop_oneof![decimal(), prefixed(), float()]) {
+        let tokens = lex(&literal.text);
+
+        prop_assert_eq!(tokens.len(), 1);
+        prop_assert_eq!(tokens[0].kind(), TokenType::NumericalLit(literal.value));
+        prop_assert_eq!(tokens[0].value(), Some(literal.text));
+    }
+
+    #[test]
+    fn malformed_literals_error(text in invalid()) {
+        let error = Lexer::new(&text).tokenize().expect_err("literal should not lex");
+
+        let error = LexError::from_io(&error);
+
+        prop_assert!(matches!(error, Some(LexError::InvalidNumber { .. })), "{:?}", error);
+        prop_assert_eq!(error.unwrap().span().start, 0);
+    }
+}
+
+#[test]
+pub fn numeric_as_f64() {
+    assert_eq!(Numeric::Int(-3).as_f64(), -3.0);
+    assert_eq!(Numeric::Float(1.5).as_f64(), 1.5);
+    assert_eq!(Numeric::LInt(2147483648).as_f64(), 2147483648.0);
+    // beyond 2^53 a float can not hold every integer.
+    assert_eq!(Numeric::LInt((1 << 53) + 1).as_f64(), (1u64 << 53) as f64);
+}
+
+#[test]
+pub fn numeric_as_i64() {
+    assert_eq!(Numeric::Int(-3).as_i64(), Some(-3));
+    assert_eq!(Numeric::LInt(i64::MAX as i128).as_i64(), Some(i64::MAX));
+    assert_eq!(Numeric::LInt(i64::MAX as i128 + 1).as_i64(), None);
+    assert_eq!(Numeric::Float(1.9).as_i64(), Some(1));
+    assert_eq!(Numeric::Float(-1.9).as_i64(), Some(-1));
+    assert_eq!(Numeric::Float(i64::MIN as f64).as_i64(), Some(i64::MIN));
+    assert_eq!(Numeric::Float(2f64.powi(63)).as_i64(), None);
+    assert_eq!(Numeric::Float(f64::INFINITY).as_i64(), None);
+    assert_eq!(Numeric::Float(f64::NAN).as_i64(), None);
+}
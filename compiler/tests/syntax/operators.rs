@@ -0,0 +1,559 @@
+use compiler::syntax::{
+    ast::{
+        keyword::Keyword,
+        operator::{Arity, OperatorKind},
+    },
+    lex::{
+        hint::is_short_ternary,
+        token::{AccessType, Numeric, StringType, TokenType},
+    },
+};
+
+use super::{arrow, lex, lex_text};
+
+fn op(text: &str) -> (TokenType, String) {
+    (TokenType::Operator, text.to_string())
+}
+
+fn var(name: &str) -> [(TokenType, String); 2] {
+    [
+        (TokenType::Variable, "$".to_string()),
+        (TokenType::Identifier, name.to_string()),
+    ]
+}
+
+fn nullsafe() -> (TokenType, String) {
+    (
+        TokenType::Accessor(AccessType::NullsafeMember),
+        "?->".to_string(),
+    )
+}
+
+fn question() -> (TokenType, String) {
+    (TokenType::QuestionMark, "?".to_string())
+}
+
+#[test]
+pub fn coalesce_chain() {
+    let mut expected = Vec::new();
+    expected.extend(var("a"));
+    expected.push(op("??"));
+    expected.extend(var("b"));
+    expected.push(op("??"));
+    expected.extend(var("c"));
+
+    assert_eq!(lex_text("$a ?? $b ?? $c"), expected);
+}
+
+#[test]
+pub fn coalesce_array_default() {
+    let mut expected = Vec::new();
+    expected.extend(var("arr"));
+    expected.push((TokenType::LeftBracket, "[".to_string()));
+    expected.push((TokenType::StringLit(StringType::Single), "k".to_string()));
+    expected.push((TokenType::RightBracket, "]".to_string()));
+    expected.push(op("??"));
+    expected.push((
+        TokenType::StringLit(StringType::Single),
+        "default".to_string(),
+    ));
+
+    assert_eq!(lex_text("$arr['k'] ?? 'default'"), expected);
+}
+
+#[test]
+pub fn coalesce_assign() {
+    let mut expected = Vec::new();
+    expected.extend(var("a"));
+    expected.push(op("??="));
+    expected.extend(var("b"));
+
+    assert_eq!(lex_text("$a ??= $b"), expected);
+}
+
+#[test]
+pub fn coalesce_parameter_default() {
+    let mut expected = vec![
+        (
+            TokenType::Keyword(Keyword::Function),
+            "function".to_string(),
+        ),
+        (TokenType::Identifier, "f".to_string()),
+        (TokenType::LeftParenthesis, "(".to_string()),
+    ];
+    expected.extend(var("x"));
+    expected.push(op("="));
+    expected.extend(var("a"));
+    expected.push(op("??"));
+    expected.push((TokenType::StringLit(StringType::Single), "d".to_string()));
+    expected.push((TokenType::RightParenthesis, ")".to_string()));
+
+    assert_eq!(lex_text("function f($x = $a ?? 'd')"), expected);
+    assert_eq!(lex_text("function f($x=$a??'d')"), expected);
+    assert_eq!(
+        operator_kinds("function f($x = $a ?? 'd')"),
+        vec![OperatorKind::Assign, OperatorKind::Coalesce]
+    );
+}
+
+#[test]
+pub fn coalesce_assign_element() {
+    let mut expected = Vec::new();
+    expected.extend(var("arr"));
+    expected.push((TokenType::LeftBracket, "[".to_string()));
+    expected.push((TokenType::StringLit(StringType::Single), "k".to_string()));
+    expected.push((TokenType::RightBracket, "]".to_string()));
+    expected.push(op("??="));
+    expected.push((
+        TokenType::StringLit(StringType::Single),
+        "default".to_string(),
+    ));
+
+    assert_eq!(lex_text("$arr['k'] ??= 'default'"), expected);
+    assert_eq!(lex_text("$arr['k']??='default'"), expected);
+}
+
+#[test]
+pub fn coalesce_assign_nested_element() {
+    let mut expected = Vec::new();
+    expected.extend(var("arr"));
+    expected.push((TokenType::LeftBracket, "[".to_string()));
+    expected.extend(var("i"));
+    expected.push((TokenType::RightBracket, "]".to_string()));
+    expected.push((TokenType::LeftBracket, "[".to_string()));
+    expected.extend(var("j"));
+    expected.push((TokenType::RightBracket, "]".to_string()));
+    expected.push(op("??="));
+    expected.push((TokenType::NumericalLit(Numeric::Int(0)), "0".to_string()));
+
+    assert_eq!(lex_text("$arr[$i][$j] ??= 0"), expected);
+}
+
+#[test]
+pub fn nullsafe_member() {
+    let mut expected = Vec::new();
+    expected.extend(var("a"));
+    expected.push(nullsafe());
+    expected.push((TokenType::Identifier, "b".to_string()));
+
+    assert_eq!(lex_text("$a?->b"), expected);
+}
+
+#[test]
+pub fn question_initial_adjacent() {
+    // `$a??$b??=$c?->d?$e:$f`
+    let mut expected = Vec::new();
+    expected.extend(var("a"));
+    expected.push(op("??"));
+    expected.extend(var("b"));
+    expected.push(op("??="));
+    expected.extend(var("c"));
+    expected.push(nullsafe());
+    expected.push((TokenType::Identifier, "d".to_string()));
+    expected.push(question());
+    expected.extend(var("e"));
+    expected.push((TokenType::Colon, ":".to_string()));
+    expected.extend(var("f"));
+
+    assert_eq!(lex_text("$a??$b??=$c?->d?$e:$f"), expected);
+}
+
+#[test]
+pub fn ternary_before_negation() {
+    // `?-` is a ternary followed by a minus, not a nullsafe access.
+    let mut expected = Vec::new();
+    expected.extend(var("a"));
+    expected.push(question());
+    expected.push(op("-"));
+    expected.extend(var("b"));
+    expected.push((TokenType::Colon, ":".to_string()));
+    expected.extend(var("c"));
+
+    assert_eq!(lex_text("$a?-$b:$c"), expected);
+}
+
+fn operator_kinds(script: &str) -> Vec<OperatorKind> {
+    lex(script)
+        .iter()
+        .filter_map(|t| t.operator_kind())
+        .collect()
+}
+
+#[test]
+pub fn spaceship_sort_callback() {
+    let mut expected = vec![
+        (TokenType::Keyword(Keyword::Fn), "fn".to_string()),
+        (TokenType::LeftParenthesis, "(".to_string()),
+    ];
+    expected.extend(var("a"));
+    expected.push((TokenType::Comma, ",".to_string()));
+    expected.extend(var("b"));
+    expected.push((TokenType::RightParenthesis, ")".to_string()));
+    expected.push(op("=>"));
+    expected.extend(var("a"));
+    expected.push(op("<=>"));
+    expected.extend(var("b"));
+
+    assert_eq!(lex_text("fn ($a, $b) => $a <=> $b"), expected);
+    assert_eq!(
+        operator_kinds("fn ($a, $b) => $a <=> $b"),
+        vec![OperatorKind::DoubleArrow, OperatorKind::Spaceship]
+    );
+}
+
+#[test]
+pub fn comparison_maximal_munch() {
+    assert_eq!(
+        operator_kinds("$a <=> $b <= $c < $d >= $e > $f"),
+        vec![
+            OperatorKind::Spaceship,
+            OperatorKind::LessEqual,
+            OperatorKind::Less,
+            OperatorKind::GreaterEqual,
+            OperatorKind::Greater,
+        ]
+    );
+    assert_eq!(
+        operator_kinds("$a === $b !== $c == $d != $e <> $f"),
+        vec![
+            OperatorKind::Identical,
+            OperatorKind::NotIdentical,
+            OperatorKind::Equal,
+            OperatorKind::NotEqual,
+            OperatorKind::NotEqual,
+        ]
+    );
+}
+
+#[test]
+pub fn word_operators_are_keywords() {
+    let tokens = lex("$a and $order or $android xor $xorg");
+
+    assert_eq!(tokens[2].kind(), TokenType::Keyword(Keyword::And));
+    assert_eq!(tokens[4].value().as_deref(), Some("order"));
+    assert_eq!(tokens[5].kind(), TokenType::Keyword(Keyword::Or));
+    assert_eq!(tokens[7].value().as_deref(), Some("android"));
+    assert_eq!(tokens[8].kind(), TokenType::Keyword(Keyword::Xor));
+    assert_eq!(tokens[10].value().as_deref(), Some("xorg"));
+}
+
+#[test]
+pub fn assignment_operators() {
+    let assignments = [
+        ("=", OperatorKind::Assign),
+        ("+=", OperatorKind::AddAssign),
+        ("-=", OperatorKind::SubAssign),
+        ("*=", OperatorKind::MulAssign),
+        ("/=", OperatorKind::DivAssign),
+        ("%=", OperatorKind::ModAssign),
+        ("**=", OperatorKind::PowAssign),
+        (".=", OperatorKind::ConcatAssign),
+        ("&=", OperatorKind::BitAndAssign),
+        ("|=", OperatorKind::BitOrAssign),
+        ("^=", OperatorKind::BitXorAssign),
+        ("<<=", OperatorKind::ShiftLeftAssign),
+        (">>=", OperatorKind::ShiftRightAssign),
+        ("??=", OperatorKind::CoalesceAssign),
+    ];
+
+    for (text, kind) in assignments {
+        let script = format!("$a {} $b;", text);
+        assert_eq!(operator_kinds(&script), vec![kind], "{}", script);
+        assert!(kind.is_assignment(), "{}", text);
+        assert_eq!(kind.as_str(), text);
+    }
+}
+
+#[test]
+pub fn non_assignment_operators() {
+    let kinds = operator_kinds("$a ** $b << $c >> $d == $e <= $f => $g");
+
+    assert_eq!(
+        kinds,
+        vec![
+            OperatorKind::Pow,
+            OperatorKind::ShiftLeft,
+            OperatorKind::ShiftRight,
+            OperatorKind::Equal,
+            OperatorKind::LessEqual,
+            OperatorKind::DoubleArrow,
+        ]
+    );
+    assert!(kinds.iter().all(|kind| !kind.is_assignment()));
+}
+
+#[test]
+pub fn dynamic_property() {
+    let mut expected = Vec::new();
+    expected.extend(var("o"));
+    expected.push(arrow());
+    expected.extend(var("p"));
+
+    assert_eq!(lex_text("$o->$p"), expected);
+}
+
+#[test]
+pub fn dynamic_property_expression() {
+    let mut expected = Vec::new();
+    expected.extend(var("o"));
+    expected.push(arrow());
+    expected.push((TokenType::LeftBrace, "{".to_string()));
+    expected.extend(var("x"));
+    expected.push((TokenType::Dot, ".".to_string()));
+    expected.push((TokenType::StringLit(StringType::Single), "y".to_string()));
+    expected.push((TokenType::RightBrace, "}".to_string()));
+
+    assert_eq!(lex_text("$o->{$x . 'y'}"), expected);
+}
+
+#[test]
+pub fn method_call() {
+    let mut expected = Vec::new();
+    expected.extend(var("o"));
+    expected.push(arrow());
+    expected.push((TokenType::Identifier, "method".to_string()));
+    expected.push((TokenType::LeftParenthesis, "(".to_string()));
+    expected.push((TokenType::RightParenthesis, ")".to_string()));
+
+    assert_eq!(lex_text("$o->method()"), expected);
+}
+
+#[test]
+pub fn silenced_variable() {
+    let mut expected = vec![op("@")];
+    expected.extend(var("x"));
+
+    assert_eq!(lex_text("@$x"), expected);
+    assert_eq!(operator_kinds("@$x"), vec![OperatorKind::Silence]);
+}
+
+#[test]
+pub fn silenced_call() {
+    assert_eq!(
+        lex_text("@foo()"),
+        vec![
+            op("@"),
+            (TokenType::Identifier, "foo".to_string()),
+            (TokenType::LeftParenthesis, "(".to_string()),
+            (TokenType::RightParenthesis, ")".to_string()),
+        ]
+    );
+}
+
+#[test]
+pub fn silenced_method_chain() {
+    let mut expected = vec![op("@")];
+    expected.extend(var("obj"));
+    expected.push(arrow());
+    expected.push((TokenType::Identifier, "method".to_string()));
+    expected.push((TokenType::LeftParenthesis, "(".to_string()));
+    expected.push((TokenType::RightParenthesis, ")".to_string()));
+
+    assert_eq!(lex_text("@$obj->method()"), expected);
+}
+
+#[test]
+pub fn silenced_parenthesized_new() {
+    assert_eq!(
+        lex_text("@(new X)->y()"),
+        vec![
+            op("@"),
+            (TokenType::LeftParenthesis, "(".to_string()),
+            (TokenType::Keyword(Keyword::New), "new".to_string()),
+            (TokenType::Identifier, "X".to_string()),
+            (TokenType::RightParenthesis, ")".to_string()),
+            arrow(),
+            (TokenType::Identifier, "y".to_string()),
+            (TokenType::LeftParenthesis, "(".to_string()),
+            (TokenType::RightParenthesis, ")".to_string()),
+        ]
+    );
+}
+
+fn ellipsis() -> (TokenType, String) {
+    (TokenType::Ellipsis, "...".to_string())
+}
+
+#[test]
+pub fn typed_variadic_parameter() {
+    let mut expected = vec![
+        (
+            TokenType::Keyword(Keyword::Function),
+            "function".to_string(),
+        ),
+        (TokenType::Identifier, "f".to_string()),
+        (TokenType::LeftParenthesis, "(".to_string()),
+        (TokenType::Identifier, "int".to_string()),
+        ellipsis(),
+    ];
+    expected.extend(var("nums"));
+    expected.push((TokenType::RightParenthesis, ")".to_string()));
+
+    assert_eq!(lex_text("function f(int ...$nums)"), expected);
+    assert_eq!(lex_text("function f(int...$nums)"), expected);
+}
+
+#[test]
+pub fn variadic_parameters() {
+    let mut expected = vec![ellipsis()];
+    expected.extend(var("a"));
+    assert_eq!(lex_text("...$a"), expected);
+
+    let mut expected = vec![(TokenType::Identifier, "string".to_string()), ellipsis()];
+    expected.extend(var("b"));
+    assert_eq!(lex_text("string ...$b"), expected);
+}
+
+#[test]
+pub fn spread_arguments() {
+    let kinds: Vec<_> = lex("f(...$args, ...[1, 2]);")
+        .iter()
+        .map(|t| t.kind())
+        .collect();
+
+    assert_eq!(kinds[2], TokenType::Ellipsis);
+    assert_eq!(kinds[6], TokenType::Ellipsis);
+    assert_eq!(kinds[7], TokenType::LeftBracket);
+    // a concatenation is not a spread.
+    assert_eq!(lex_text("$a.$b")[2], (TokenType::Dot, ".".to_string()));
+}
+
+#[test]
+pub fn operator_arity() {
+    let arities = [
+        ("-", Arity::Both),
+        ("+", Arity::Both),
+        ("&", Arity::Both),
+        ("!", Arity::Unary),
+        ("~", Arity::Unary),
+        ("@", Arity::Unary),
+        ("*", Arity::Binary),
+        ("=", Arity::Binary),
+        ("??", Arity::Binary),
+    ];
+
+    for (text, arity) in arities {
+        let kind: OperatorKind = text.parse().unwrap();
+        assert_eq!(kind.arity(), arity, "{}", text);
+    }
+}
+
+#[test]
+pub fn reference_assignment() {
+    let expected = [var("a").as_slice(), &[op("="), op("&")], &var("b")].concat();
+    assert_eq!(lex_text("$a = &$b;")[..6], expected);
+    assert_eq!(lex_text("$a =& $b;")[..6], expected);
+    assert_eq!(lex_text("$a=&$b;"), lex_text("$a = &$b;"));
+    assert_eq!(
+        operator_kinds("$a =& $b;"),
+        vec![OperatorKind::Assign, OperatorKind::BitAnd]
+    );
+}
+
+#[test]
+pub fn reference_assignment_of_call() {
+    assert_eq!(
+        lex_text("$a =& f();"),
+        [
+            var("a").as_slice(),
+            &[
+                op("="),
+                op("&"),
+                (TokenType::Identifier, "f".to_string()),
+                (TokenType::LeftParenthesis, "(".to_string()),
+                (TokenType::RightParenthesis, ")".to_string()),
+                (TokenType::EOS, ";".to_string()),
+            ],
+        ]
+        .concat()
+    );
+    // `&=` is still a single operator.
+    assert_eq!(
+        operator_kinds("$a &= $b;"),
+        vec![OperatorKind::BitAndAssign]
+    );
+}
+
+fn colon() -> (TokenType, String) {
+    (TokenType::Colon, ":".to_string())
+}
+
+#[test]
+pub fn coalesce_then_short_ternary() {
+    let expected = [
+        var("a").as_slice(),
+        &[op("??")],
+        &var("b"),
+        &[question(), colon()],
+        &var("c"),
+    ]
+    .concat();
+    assert_eq!(lex_text("$a ?? $b ?: $c"), expected);
+    assert_eq!(lex_text("$a??$b?:$c"), expected);
+    // a short ternary may be split, the `?` and `:` are separate tokens either way.
+    assert_eq!(lex_text("$a ?? $b ? : $c"), expected);
+
+    let tokens = lex("$a ?? $b ?: $c");
+    let mark = tokens.iter().position(|t| t.0 == TokenType::QuestionMark);
+    assert!(is_short_ternary(&tokens, mark.unwrap()));
+}
+
+#[test]
+pub fn coalesce_then_ternary() {
+    let tokens = lex("$a ?? $b ? $c : $d");
+    assert_eq!(
+        lex_text("$a ?? $b ? $c : $d"),
+        [
+            var("a").as_slice(),
+            &[op("??")],
+            &var("b"),
+            &[question()],
+            &var("c"),
+            &[colon()],
+            &var("d"),
+        ]
+        .concat()
+    );
+    let mark = tokens.iter().position(|t| t.0 == TokenType::QuestionMark);
+    assert!(!is_short_ternary(&tokens, mark.unwrap()));
+    // `???` is not an operator, but a coalesce followed by a `?`.
+    assert_eq!(lex_text("$a ??? $b")[2..4], [op("??"), question()]);
+}
+
+fn ident(name: &str) -> (TokenType, String) {
+    (TokenType::Identifier, name.to_string())
+}
+
+#[test]
+pub fn mixed_nullsafe_chain() {
+    let mut expected = Vec::new();
+    expected.extend(var("a"));
+    expected.push(nullsafe());
+    expected.push(ident("b"));
+    expected.push(arrow());
+    expected.push(ident("c"));
+    expected.push(nullsafe());
+    expected.push(ident("d"));
+    expected.push((TokenType::LeftParenthesis, "(".to_string()));
+    expected.push((TokenType::RightParenthesis, ")".to_string()));
+
+    assert_eq!(lex_text("$a?->b->c?->d()"), expected);
+    assert_eq!(lex_text("$a ?-> b -> c ?-> d ( )"), expected);
+}
+
+#[test]
+pub fn ternary_after_nullsafe_chain() {
+    // the `?` following a property begins a ternary unless it is directly followed by `->`.
+    assert_eq!(
+        lex_text("$a?->b ? $c->d : $e?->f"),
+        [
+            var("a").as_slice(),
+            &[nullsafe(), ident("b"), question()],
+            &var("c"),
+            &[arrow(), ident("d"), (TokenType::Colon, ":".to_string())],
+            &var("e"),
+            &[nullsafe(), ident("f")],
+        ]
+        .concat()
+    );
+}
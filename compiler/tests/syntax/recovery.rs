@@ -0,0 +1,71 @@
+use compiler::syntax::lex::{span::Span, token::TokenType, Lexer};
+
+fn next_significant(lexer: &mut Lexer) -> Option<TokenType> {
+    while let Some(token) = lexer.next().unwrap() {
+        if token.kind() != TokenType::Whitespace {
+            return Some(token.kind());
+        }
+    }
+    None
+}
+
+#[test]
+pub fn resume_after_statement() {
+    let mut lexer = Lexer::new("$a = = (1 + ;\n$b = 2;");
+    for _ in 0..5 {
+        lexer.next().unwrap();
+    }
+
+    // the parser fails at the second `=`.
+    assert_eq!(lexer.skip_to_statement_boundary(), Span::new(5, 13));
+    assert_eq!(next_significant(&mut lexer), Some(TokenType::Variable));
+    assert_eq!(next_significant(&mut lexer), Some(TokenType::Identifier));
+}
+
+#[test]
+pub fn resume_after_lex_error() {
+    let mut lexer = Lexer::new("$a € 1; $b");
+    while let Ok(Some(_)) = lexer.next() {}
+
+    assert_eq!(lexer.skip_to_statement_boundary(), Span::new(6, 9));
+    assert_eq!(next_significant(&mut lexer), Some(TokenType::Variable));
+}
+
+#[test]
+pub fn skip_nested_block() {
+    let mut lexer = Lexer::new("if ($x) { f(; } $c;");
+
+    assert_eq!(lexer.skip_to_statement_boundary(), Span::new(0, 15));
+    assert_eq!(next_significant(&mut lexer), Some(TokenType::Variable));
+}
+
+#[test]
+pub fn stop_before_enclosing_brace() {
+    let mut lexer = Lexer::new("{ $a = ( } $c;");
+    lexer.next().unwrap();
+
+    assert_eq!(lexer.skip_to_statement_boundary(), Span::new(1, 9));
+    assert_eq!(next_significant(&mut lexer), Some(TokenType::RightBrace));
+}
+
+#[test]
+pub fn skip_to_end() {
+    let mut lexer = Lexer::new("$a = 1");
+
+    assert_eq!(lexer.skip_to_statement_boundary(), Span::new(0, 6));
+    assert_eq!(lexer.next().unwrap(), None);
+}
+
+#[test]
+pub fn resume_after_invalid_operator() {
+    for script in ["<?php $a ??-> b; $c = 1;", "<?php $a =>= b; $c = 1;"] {
+        let mut lexer = Lexer::new(script);
+        while let Ok(Some(_)) = lexer.next() {}
+
+        // the invalid operator was eaten, so skipping does not fail on it again.
+        lexer.skip_to_statement_boundary();
+        assert_eq!(next_significant(&mut lexer), Some(TokenType::Variable));
+        let name = lexer.next().unwrap().unwrap();
+        assert_eq!(name.value().as_deref(), Some("c"), "{}", script);
+    }
+}
@@ -0,0 +1,71 @@
+use compiler::syntax::lex::{
+    span::Span,
+    token::{Numeric, TokenType},
+    Lexer,
+};
+
+use super::lex;
+
+#[test]
+pub fn merge_adjacent() {
+    assert_eq!(
+        Span::merge(Span::new(0, 3), Span::new(3, 5)),
+        Span::new(0, 5)
+    );
+}
+
+#[test]
+pub fn merge_apart() {
+    let merged = Span::new(0, 5);
+
+    assert_eq!(Span::merge(Span::new(0, 2), Span::new(4, 5)), merged);
+    assert_eq!(Span::merge(Span::new(4, 5), Span::new(0, 2)), merged);
+    assert_eq!(Span::merge(merged, Span::new(1, 2)), merged);
+}
+
+#[test]
+pub fn cover_spans() {
+    let spans = [Span::new(4, 6), Span::new(1, 2), Span::new(8, 9)];
+
+    assert_eq!(Span::cover(&spans), Some(Span::new(1, 9)));
+    assert_eq!(Span::cover(&spans[..1]), Some(Span::new(4, 6)));
+    assert_eq!(Span::cover(&[]), None);
+}
+
+#[test]
+pub fn cover_tokens() {
+    let spans: Vec<Span> = lex("$a = foo(1);").iter().map(|t| t.span()).collect();
+    let call = Span::cover(&spans[3..7]).unwrap();
+
+    assert_eq!(call, Span::new(5, 11));
+    assert_eq!(call.len(), 6);
+    assert_eq!(std::ops::Range::from(call), 5..11);
+}
+
+#[test]
+pub fn lex_all_with_spans() {
+    let pairs: Vec<(TokenType, Span)> = Lexer::new("$a = 1;")
+        .lex_all_with_spans()
+        .unwrap()
+        .into_iter()
+        .map(|(token, span)| (token.kind(), span))
+        .collect();
+
+    assert_eq!(
+        pairs,
+        vec![
+            (TokenType::Variable, Span::new(0, 1)),
+            (TokenType::Identifier, Span::new(1, 2)),
+            (TokenType::Whitespace, Span::new(2, 3)),
+            (TokenType::Operator, Span::new(3, 4)),
+            (TokenType::Whitespace, Span::new(4, 5)),
+            (TokenType::NumericalLit(Numeric::Int(1)), Span::new(5, 6)),
+            (TokenType::EOS, Span::new(6, 7)),
+        ]
+    );
+}
+
+#[test]
+pub fn lex_all_with_spans_stops_on_error() {
+    assert!(Lexer::new("$a = `ls`;").lex_all_with_spans().is_err());
+}
@@ -0,0 +1,44 @@
+use std::io::Error;
+
+use compiler::syntax::lex::{error::LexError, stream::TokenStream, token::Token, Lexer};
+use futures::{executor::block_on, stream, StreamExt};
+
+fn lex_chunks(chunks: &[&[u8]]) -> Vec<Result<Token, Error>> {
+    let source = stream::iter(chunks.iter().map(|chunk| Ok::<_, Error>(chunk.to_vec())));
+    block_on(TokenStream::new(source).collect())
+}
+
+#[test]
+pub fn chunked_input_matches_tokenize() {
+    let script = "<?php\nfunction greet($name) {\n    echo \"Hello, {$name}!\" ?? 'ü';\n}\n";
+    let expected = Lexer::new(script).tokenize().unwrap();
+
+    for size in [1, 2, 3, 7, 64] {
+        let chunks: Vec<&[u8]> = script.as_bytes().chunks(size).collect();
+        let tokens: Vec<Token> = lex_chunks(&chunks)
+            .into_iter()
+            .collect::<Result<_, _>>()
+            .unwrap();
+
+        assert_eq!(tokens, expected, "chunks of {} bytes", size);
+    }
+}
+
+#[test]
+pub fn stream_ends_after_error() {
+    let results = lex_chunks(&[b"$a = ", b"1 \xE2\x82", b"\xAC 2;"]);
+
+    assert_eq!(results.len(), 8);
+    assert!(results[..7].iter().all(Result::is_ok));
+    let error = results[7].as_ref().unwrap_err();
+    assert_eq!(LexError::from_io(error).unwrap().span(), 7..10);
+}
+
+#[test]
+pub fn source_error_is_yielded() {
+    let source = stream::iter(vec![Ok(b"$a".to_vec()), Err(Error::other("disconnected"))]);
+    let results: Vec<Result<Token, Error>> = block_on(TokenStream::new(source).collect());
+
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].as_ref().unwrap_err().to_string(), "disconnected");
+}
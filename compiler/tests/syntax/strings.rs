@@ -0,0 +1,123 @@
+use compiler::syntax::lex::{
+    error::LexError,
+    token::{Location, StringType, TokenType},
+    Lexer,
+};
+
+use super::lex;
+
+#[test]
+pub fn multi_line_double_quoted() {
+    let tokens = lex("$a = \"one\ntwo\nthree\";\n$b");
+
+    let string = &tokens[3];
+    assert_eq!(string.kind(), TokenType::StringLit(StringType::Double));
+    assert_eq!(string.location().start, Location::new(1, 6));
+    assert_eq!(string.location().end, Location::new(3, 7));
+
+    let semicolon = &tokens[4];
+    assert_eq!(semicolon.location().start, Location::new(3, 7));
+
+    let variable = &tokens[5];
+    assert_eq!(variable.location().start, Location::new(4, 1));
+}
+
+#[test]
+pub fn multi_line_single_quoted() {
+    let tokens = lex("'a\nb'");
+
+    assert_eq!(
+        tokens[0].location(),
+        Location::new(1, 1)..Location::new(2, 3)
+    );
+}
+
+#[test]
+pub fn columns_count_chars() {
+    let tokens = Lexer::new("'é' $a").tokenize().unwrap();
+
+    assert_eq!(tokens[0].range(), 0..4);
+    assert_eq!(tokens[0].location().end, Location::new(1, 4));
+}
+
+fn single_quoted(script: &str) -> (String, usize) {
+    let tokens = lex(script);
+    assert_eq!(tokens[0].kind(), TokenType::StringLit(StringType::Single));
+    (tokens[0].value().unwrap(), tokens.len())
+}
+
+#[test]
+pub fn single_quoted_backslash() {
+    assert_eq!(single_quoted(r"'\\'"), (r"\".to_string(), 1));
+    assert_eq!(single_quoted(r"'a\\'"), (r"a\".to_string(), 1));
+}
+
+#[test]
+pub fn single_quoted_quote() {
+    assert_eq!(single_quoted(r"'\''"), ("'".to_string(), 1));
+    assert_eq!(single_quoted(r"'it\'s';"), ("it's".to_string(), 2));
+}
+
+#[test]
+pub fn single_quoted_literal_backslash() {
+    assert_eq!(single_quoted(r"'\n'"), (r"\n".to_string(), 1));
+    assert_eq!(single_quoted(r"'a\n'"), (r"a\n".to_string(), 1));
+}
+
+fn double_quoted(script: &str) -> String {
+    let tokens = lex(script);
+    assert_eq!(tokens[0].kind(), TokenType::StringLit(StringType::Double));
+    assert_eq!(tokens.len(), 1, "{:?}", tokens);
+    tokens[0].value().unwrap()
+}
+
+fn escape_error(script: &str) -> LexError {
+    let error = Lexer::new(script).tokenize().err().unwrap();
+    LexError::from_io(&error).unwrap().clone()
+}
+
+#[test]
+pub fn double_quoted_escapes() {
+    assert_eq!(double_quoted(r#""a\tb\n""#), "a\tb\n");
+    assert_eq!(double_quoted(r#""\"\\\$""#), "\"\\$");
+    assert_eq!(double_quoted(r#""\e\v\f\r""#), "\u{1b}\u{b}\u{c}\r");
+    assert_eq!(double_quoted(r#""\q""#), r"\q");
+}
+
+#[test]
+pub fn hex_escape() {
+    assert_eq!(double_quoted(r#""\x41""#), "A");
+    assert_eq!(double_quoted(r#""\x4g""#), "\u{4}g");
+    assert_eq!(double_quoted(r#""\xg""#), r"\xg");
+}
+
+#[test]
+pub fn octal_escape() {
+    assert_eq!(double_quoted(r#""\101""#), "A");
+    assert_eq!(double_quoted(r#""\0""#), "\0");
+    assert_eq!(double_quoted(r#""\1018""#), "A8");
+    assert_eq!(double_quoted(r#""\777""#), "\u{ff}");
+}
+
+#[test]
+pub fn unicode_escape() {
+    assert_eq!(double_quoted(r#""\u{1F600}""#), "😀");
+    assert_eq!(double_quoted(r#""\u{41}\u{e9}""#), "Aé");
+    assert_eq!(double_quoted(r#""é""#), r"é");
+}
+
+#[test]
+pub fn invalid_unicode_escape() {
+    assert_eq!(
+        escape_error(r#"$a = "\u{D800}";"#),
+        LexError::InvalidEscape { span: 6..14 }
+    );
+    assert_eq!(
+        escape_error(r#""\u{110000}""#),
+        LexError::InvalidEscape { span: 1..11 }
+    );
+    assert_eq!(
+        escape_error(r#""\u{}""#),
+        LexError::InvalidEscape { span: 1..5 }
+    );
+}
@@ -0,0 +1,335 @@
+use compiler::syntax::lex::{
+    token::{Numeric, TokenType},
+    warning::WarningCode,
+    HtmlMode, Lexer,
+};
+
+use super::{kinds, lex_text};
+
+#[test]
+pub fn open_tags() {
+    assert_eq!(
+        lex_text("<?php echo"),
+        vec![
+            (TokenType::OpenTag, "<?php".to_string()),
+            (TokenType::Identifier, "echo".to_string())
+        ]
+    );
+    assert_eq!(lex_text("<?=")[0], (TokenType::OpenTag, "<?=".to_string()));
+    assert_eq!(lex_text("<? ")[0], (TokenType::OpenTag, "<?".to_string()));
+    // `<?phpx` is a short open tag followed by an identifier.
+    assert_eq!(
+        kinds("<?phpx"),
+        vec![TokenType::OpenTag, TokenType::Identifier]
+    );
+}
+
+#[test]
+pub fn question_mark_at_eof() {
+    assert_eq!(
+        kinds("$a ?"),
+        vec![
+            TokenType::Variable,
+            TokenType::Identifier,
+            TokenType::QuestionMark
+        ]
+    );
+}
+
+#[test]
+pub fn close_tag_at_eof() {
+    assert_eq!(
+        lex_text("<?php $a ?>").last(),
+        Some(&(TokenType::CloseTag, "?>".to_string()))
+    );
+    assert_eq!(kinds("?>"), vec![TokenType::CloseTag]);
+}
+
+#[test]
+pub fn coalesce_at_eof() {
+    assert_eq!(
+        lex_text("$a ??").last(),
+        Some(&(TokenType::Operator, "??".to_string()))
+    );
+    assert_eq!(kinds("??"), vec![TokenType::Operator]);
+}
+
+#[test]
+pub fn short_ternary_and_close_tag() {
+    // `?:` is a ternary with the middle omitted, `?>` closes the tag.
+    assert_eq!(
+        kinds("$a ?: $b ?>"),
+        vec![
+            TokenType::Variable,
+            TokenType::Identifier,
+            TokenType::QuestionMark,
+            TokenType::Colon,
+            TokenType::Variable,
+            TokenType::Identifier,
+            TokenType::CloseTag,
+        ]
+    );
+}
+
+#[test]
+pub fn expression_mode() {
+    let tokens = Lexer::new("1 + 2").expression_mode().tokenize().unwrap();
+    let kinds: Vec<TokenType> = tokens
+        .iter()
+        .map(|t| t.kind())
+        .filter(|k| *k != TokenType::Whitespace)
+        .collect();
+
+    assert!(matches!(
+        kinds.as_slice(),
+        [
+            TokenType::NumericalLit(_),
+            TokenType::Operator,
+            TokenType::NumericalLit(_)
+        ]
+    ));
+}
+
+#[test]
+pub fn expression_mode_trailing_newline() {
+    let tokens = Lexer::new("$a + 2  \n")
+        .expression_mode()
+        .tokenize()
+        .unwrap();
+    let last = &tokens[tokens.len() - 1];
+
+    assert_eq!(tokens[tokens.len() - 2].value().as_deref(), Some("  "));
+    assert_eq!(last.kind(), TokenType::EOS);
+    assert_eq!(last.range(), 8..9);
+    assert_eq!(last.text().as_deref(), Some("\n"));
+}
+
+#[test]
+pub fn expression_mode_terminated_statement() {
+    let tokens = Lexer::new("$a;\r\n").expression_mode().tokenize().unwrap();
+    let kinds: Vec<TokenType> = tokens.iter().map(|t| t.kind()).collect();
+
+    assert_eq!(
+        kinds,
+        vec![
+            TokenType::Variable,
+            TokenType::Identifier,
+            TokenType::EOS,
+            TokenType::Whitespace
+        ]
+    );
+}
+
+#[test]
+pub fn trailing_newline_is_whitespace_by_default() {
+    let tokens = Lexer::new("$a\n").tokenize().unwrap();
+
+    assert_eq!(tokens.last().unwrap().kind(), TokenType::Whitespace);
+}
+
+const TEMPLATE: &str = "<ul>\n<?php foreach ($items as $item): ?>\n  <li><?= $item ?></li>\n<?php endforeach; ?>\n</ul>\n";
+
+fn lex_html(mode: HtmlMode) -> Vec<(TokenType, String)> {
+    Lexer::new(TEMPLATE)
+        .html_mode(mode)
+        .tokenize()
+        .unwrap()
+        .into_iter()
+        .filter(|t| !matches!(t.kind(), TokenType::Whitespace))
+        .map(|t| (t.kind(), t.value().unwrap_or_default()))
+        .collect()
+}
+
+fn html(text: &str) -> (TokenType, String) {
+    (TokenType::InlineHtml, text.to_string())
+}
+
+#[test]
+pub fn inline_html_opaque() {
+    let tokens = lex_html(HtmlMode::Opaque);
+    let inline: Vec<_> = tokens
+        .iter()
+        .filter(|(kind, _)| *kind == TokenType::InlineHtml)
+        .cloned()
+        .collect();
+
+    assert_eq!(
+        inline,
+        vec![
+            html("<ul>\n"),
+            html("  <li>"),
+            html("</li>\n"),
+            html("</ul>\n")
+        ]
+    );
+    assert_eq!(tokens[0], html("<ul>\n"));
+    assert_eq!(tokens[1], (TokenType::OpenTag, "<?php".to_string()));
+    // the line break after a close tag is not output by PHP, so it is part of the tag.
+    assert!(tokens.contains(&(TokenType::CloseTag, "?>\n".to_string())));
+}
+
+#[test]
+pub fn inline_html_skipped() {
+    let tokens = lex_html(HtmlMode::Skip);
+
+    assert!(tokens
+        .iter()
+        .all(|(kind, _)| *kind != TokenType::InlineHtml));
+    assert_eq!(tokens[0], (TokenType::OpenTag, "<?php".to_string()));
+    assert_eq!(
+        tokens
+            .iter()
+            .filter(|(kind, _)| *kind == TokenType::OpenTag)
+            .count(),
+        3
+    );
+    assert_eq!(
+        tokens.last(),
+        Some(&(TokenType::CloseTag, "?>\n".to_string()))
+    );
+
+    let opaque = lex_html(HtmlMode::Opaque);
+    let code: Vec<_> = opaque
+        .into_iter()
+        .filter(|(kind, _)| *kind != TokenType::InlineHtml)
+        .collect();
+    assert_eq!(tokens, code);
+}
+
+#[test]
+pub fn inline_html_without_tags() {
+    let tokens = Lexer::new("<p>hi</p>")
+        .html_mode(HtmlMode::Opaque)
+        .tokenize()
+        .unwrap();
+
+    assert_eq!(tokens.len(), 1);
+    assert_eq!(tokens[0].kind(), TokenType::InlineHtml);
+    assert!(Lexer::new("<p>hi</p>")
+        .html_mode(HtmlMode::Skip)
+        .tokenize()
+        .unwrap()
+        .is_empty());
+}
+
+fn lex_opaque(script: &str) -> Vec<(TokenType, String)> {
+    Lexer::new(script)
+        .html_mode(HtmlMode::Opaque)
+        .tokenize()
+        .unwrap()
+        .into_iter()
+        .map(|t| (t.kind(), t.value().unwrap_or_default()))
+        .collect()
+}
+
+#[test]
+pub fn html_after_final_close_tag() {
+    let tokens = lex_opaque("<?php echo 1; ?>\nHello");
+
+    assert_eq!(
+        tokens[tokens.len() - 2..],
+        [(TokenType::CloseTag, "?>\n".to_string()), html("Hello")]
+    );
+    // only the first line break is eaten, the rest is output.
+    let tokens = lex_opaque("<?php echo 1; ?>\r\n\r\nHello\n");
+    assert_eq!(
+        tokens[tokens.len() - 2..],
+        [
+            (TokenType::CloseTag, "?>\r\n".to_string()),
+            html("\r\nHello\n")
+        ]
+    );
+}
+
+#[test]
+pub fn trailing_newline_after_final_close_tag() {
+    let tokens = lex_opaque("<?php echo 1; ?>\n");
+
+    assert_eq!(
+        tokens.last(),
+        Some(&(TokenType::CloseTag, "?>\n".to_string()))
+    );
+    assert!(tokens
+        .iter()
+        .all(|(kind, _)| *kind != TokenType::InlineHtml));
+    assert_eq!(
+        lex_opaque("<?php echo 1; ?>").last(),
+        Some(&(TokenType::CloseTag, "?>".to_string()))
+    );
+}
+
+fn lex_legacy(script: &str) -> (Vec<(TokenType, String)>, Vec<WarningCode>) {
+    let mut lexer = Lexer::new(script).html_mode(HtmlMode::Opaque).legacy_tags();
+    let mut tokens = Vec::new();
+    while let Some(token) = lexer.next().unwrap() {
+        if token.kind() != TokenType::Whitespace {
+            tokens.push((token.kind(), token.value().unwrap_or_default()));
+        }
+    }
+    let warnings = lexer.warnings().iter().map(|w| w.code).collect();
+    (tokens, warnings)
+}
+
+#[test]
+pub fn asp_tags_legacy() {
+    let (tokens, warnings) = lex_legacy("<p><% echo $a; %>\n<%= $b %></p>");
+
+    assert_eq!(
+        kinds_of(&tokens),
+        vec![
+            TokenType::InlineHtml,
+            TokenType::OpenTag,
+            TokenType::Identifier,
+            TokenType::Variable,
+            TokenType::Identifier,
+            TokenType::EOS,
+            TokenType::CloseTag,
+            TokenType::OpenTag,
+            TokenType::Variable,
+            TokenType::Identifier,
+            TokenType::CloseTag,
+            TokenType::InlineHtml,
+        ]
+    );
+    assert_eq!(tokens[1].1, "<%");
+    assert_eq!(tokens[6].1, "%>\n");
+    assert_eq!(tokens[7].1, "<%=");
+    assert_eq!(tokens[11], html("</p>"));
+    assert_eq!(warnings, vec![WarningCode::AspTag; 4]);
+}
+
+#[test]
+pub fn script_tag_legacy() {
+    let (tokens, warnings) = lex_legacy("<b><script language=\"php\">echo 1;</script >\n</b>");
+
+    assert_eq!(
+        kinds_of(&tokens),
+        vec![
+            TokenType::InlineHtml,
+            TokenType::OpenTag,
+            TokenType::Identifier,
+            TokenType::NumericalLit(Numeric::Int(1)),
+            TokenType::EOS,
+            TokenType::CloseTag,
+            TokenType::InlineHtml,
+        ]
+    );
+    assert_eq!(tokens[1].1, "<script language=\"php\">");
+    assert_eq!(tokens[5].1, "</script >\n");
+    assert_eq!(warnings, vec![WarningCode::ScriptTag]);
+}
+
+#[test]
+pub fn legacy_tags_off_by_default() {
+    let tokens = Lexer::new("<p><% echo 1; %></p>")
+        .html_mode(HtmlMode::Opaque)
+        .tokenize()
+        .unwrap();
+
+    assert_eq!(tokens.len(), 1);
+    assert_eq!(tokens[0].kind(), TokenType::InlineHtml);
+}
+
+fn kinds_of(tokens: &[(TokenType, String)]) -> Vec<TokenType> {
+    tokens.iter().map(|(kind, _)| kind.clone()).collect()
+}
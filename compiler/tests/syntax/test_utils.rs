@@ -0,0 +1,63 @@
+use compiler::syntax::lex::{
+    test_utils::{assert_tokens_eq, tokens_diff},
+    token::TokenType,
+};
+
+use super::lex;
+
+#[test]
+pub fn tokens_equal() {
+    assert_tokens_eq(
+        &lex("$a;"),
+        &[
+            (TokenType::Variable, "$"),
+            (TokenType::Identifier, "a"),
+            (TokenType::EOS, ";"),
+        ],
+    );
+}
+
+#[test]
+pub fn tokens_differ() {
+    let diff = tokens_diff(
+        &lex("$a;"),
+        &[
+            (TokenType::Variable, "$"),
+            (TokenType::Identifier, "b"),
+            (TokenType::EOS, ";"),
+        ],
+    );
+
+    assert_eq!(
+        diff.as_deref(),
+        Some(
+            "tokens differ at index 1:\n  expected: Identifier \"b\"\n       got: Identifier \"a\" at 1..2"
+        )
+    );
+}
+
+#[test]
+pub fn tokens_differ_in_length() {
+    let diff = tokens_diff(&lex("$a;"), &[(TokenType::Variable, "$")]);
+
+    assert_eq!(
+        diff.as_deref(),
+        Some("expected 1 tokens, got 3, first unexpected token: Identifier \"a\" at 1..2")
+    );
+
+    let diff = tokens_diff(
+        &lex("$"),
+        &[(TokenType::Variable, "$"), (TokenType::EOS, ";")],
+    );
+
+    assert_eq!(
+        diff.as_deref(),
+        Some("expected 2 tokens, got 1, first missing token: EOS \";\"")
+    );
+}
+
+#[test]
+#[should_panic(expected = "tokens differ at index 0")]
+pub fn assert_tokens_panics() {
+    assert_tokens_eq(&lex("a"), &[(TokenType::EOS, ";")]);
+}
@@ -0,0 +1,339 @@
+use std::ops::ControlFlow;
+
+use compiler::syntax::{
+    ast::keyword::Keyword,
+    lex::{
+        error::LexError,
+        token::{
+            token_at_offset, variable_name, Location, Numeric, StringType, TokenKind, TokenType,
+            VariableName,
+        },
+        Lexer,
+    },
+};
+
+use super::{lex, TEST_SCRIPT};
+
+fn texts(script: &str) -> Vec<String> {
+    lex(script)
+        .iter()
+        .map(|t| t.text().expect("token should have text").into_owned())
+        .collect()
+}
+
+#[test]
+pub fn text_identifier() {
+    assert_eq!(texts("foo"), vec!["foo"]);
+}
+
+#[test]
+pub fn text_string() {
+    assert_eq!(texts("'bar'"), vec!["bar"]);
+}
+
+#[test]
+pub fn text_number() {
+    assert_eq!(texts("42"), vec!["42"]);
+}
+
+#[test]
+pub fn text_keyword() {
+    assert_eq!(texts("return $x;"), vec!["return", "$", "x", ";"]);
+}
+
+#[test]
+pub fn text_operator() {
+    assert_eq!(texts("$a + 1"), vec!["$", "a", "+", "1"]);
+}
+
+#[test]
+pub fn text_structural() {
+    assert_eq!(texts("{ ( ) }"), vec!["{", "(", ")", "}"]);
+}
+
+#[test]
+pub fn byte_order_mark() {
+    let tokens = lex("\u{feff}$a = 1;");
+
+    assert_eq!(tokens[0].kind(), TokenType::BOM);
+    assert_eq!(tokens[0].range(), 0..3);
+    assert_eq!(tokens[1].kind(), TokenType::Variable);
+    assert_eq!(tokens[1].range(), 3..4);
+}
+
+#[test]
+pub fn shebang() {
+    let tokens = lex("#!/usr/bin/php\n$a = 1;");
+
+    assert_eq!(tokens[0].kind(), TokenType::Shebang);
+    assert_eq!(tokens[0].value().as_deref(), Some("#!/usr/bin/php"));
+    assert_eq!(tokens[0].range(), 0..14);
+    assert_eq!(tokens[1].kind(), TokenType::Variable);
+}
+
+#[test]
+pub fn shebang_only_first_line() {
+    let tokens = Lexer::new("$a = 1;\n#!/usr/bin/php").tokenize();
+
+    assert!(!matches!(tokens, Ok(tokens) if tokens.iter().any(|t| t.kind() == TokenType::Shebang)));
+}
+
+#[test]
+pub fn indices_contiguous() {
+    let tokens = Lexer::new("$a = foo($b, 'c');").tokenize().unwrap();
+
+    assert!(!tokens.is_empty());
+    for (i, token) in tokens.iter().enumerate() {
+        assert_eq!(token.index(), i);
+    }
+}
+
+#[test]
+pub fn indices_reset() {
+    let mut lexer = Lexer::new("$a = 1;");
+    let first = lexer.tokenize().unwrap();
+
+    lexer.reset();
+    assert_eq!(lexer.next().unwrap().unwrap().index(), 0);
+
+    lexer.reset();
+    assert_eq!(lexer.tokenize().unwrap(), first);
+}
+
+#[test]
+pub fn lex_from_file() {
+    let script = "<?php\n$a = 'from a file';\n";
+    let path = std::env::temp_dir().join(format!("rs-php-rt-{}.php", std::process::id()));
+    std::fs::write(&path, script).unwrap();
+
+    let tokens = Lexer::from_file(&path).and_then(|mut lexer| lexer.tokenize());
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(tokens.unwrap(), Lexer::new(script).tokenize().unwrap());
+}
+
+#[test]
+pub fn lex_from_missing_file() {
+    let error = Lexer::from_file("/this/file/does/not/exist.php")
+        .err()
+        .unwrap();
+
+    assert_eq!(error.kind(), std::io::ErrorKind::NotFound);
+}
+
+#[test]
+pub fn token_at_offsets() {
+    let tokens = Lexer::new("$a = 10;").tokenize().unwrap();
+    let at = |offset| token_at_offset(&tokens, offset).map(|t| t.kind());
+
+    assert_eq!(at(0), Some(TokenType::Variable));
+    assert_eq!(at(1), Some(TokenType::Identifier));
+    assert_eq!(at(2), Some(TokenType::Whitespace));
+    assert_eq!(at(3), Some(TokenType::Operator));
+    assert!(matches!(at(5), Some(TokenType::NumericalLit(_))));
+    assert!(matches!(at(6), Some(TokenType::NumericalLit(_))));
+    assert_eq!(at(7), Some(TokenType::EOS));
+    assert_eq!(at(8), None);
+}
+
+#[test]
+pub fn token_at_dropped_offset() {
+    let tokens = lex("$a = 10;");
+
+    assert_eq!(token_at_offset(&tokens, 2), None);
+    assert_eq!(token_at_offset(&tokens, 4), None);
+    assert_eq!(token_at_offset(&tokens, 3).map(|t| t.index()), Some(3));
+}
+
+#[test]
+pub fn drive_counts_tokens() {
+    let mut count = 0;
+    Lexer::new(TEST_SCRIPT)
+        .drive(|_| {
+            count += 1;
+            ControlFlow::Continue(())
+        })
+        .unwrap();
+
+    assert_eq!(count, Lexer::new(TEST_SCRIPT).tokenize().unwrap().len());
+}
+
+#[test]
+pub fn drive_stops_early() {
+    let mut lexer = Lexer::new("$a = 1; $b = 2;");
+    let mut seen = Vec::new();
+    lexer
+        .drive(|token| {
+            seen.push(token.kind());
+            if seen.len() == 3 {
+                ControlFlow::Break(())
+            } else {
+                ControlFlow::Continue(())
+            }
+        })
+        .unwrap();
+
+    assert_eq!(
+        seen,
+        vec![
+            TokenType::Variable,
+            TokenType::Identifier,
+            TokenType::Whitespace
+        ]
+    );
+    // the lexer resumes after the last token pushed.
+    assert_eq!(lexer.next().unwrap().unwrap().kind(), TokenType::Operator);
+}
+
+#[test]
+pub fn drive_returns_errors() {
+    let mut count = 0;
+    let result = Lexer::new("$a € 1").drive(|_| {
+        count += 1;
+        ControlFlow::Continue(())
+    });
+
+    assert!(result.is_err());
+    assert_eq!(count, 3);
+}
+
+#[test]
+pub fn lex_range() {
+    let script = "$a = 1;\n$b = 'é';\n$c = 3;";
+    let start = script.find("$b").unwrap();
+    let end = script.find("\n$c").unwrap();
+    let tokens = Lexer::new_range(script, start, end)
+        .unwrap()
+        .tokenize()
+        .unwrap();
+    let whole = Lexer::new(&script[start..end]).tokenize().unwrap();
+
+    assert_eq!(tokens.len(), whole.len());
+    for (token, relative) in tokens.iter().zip(&whole) {
+        assert_eq!(token.kind(), relative.kind());
+        assert_eq!(
+            token.range(),
+            relative.1.start + start..relative.1.end + start
+        );
+        assert_eq!(token.location().start.line, 2);
+    }
+    assert_eq!(tokens[0].location().start, Location::new(2, 1));
+    assert_eq!(tokens.last().unwrap().range().end, end);
+}
+
+#[test]
+pub fn lex_range_stops_at_end() {
+    // the string is cut off by the end of the range.
+    let tokens = Lexer::new_range("$a = 'bc';", 3, 7)
+        .unwrap()
+        .tokenize()
+        .unwrap();
+
+    assert_eq!(tokens.last().unwrap().range(), 5..7);
+    assert_eq!(tokens.last().unwrap().value().as_deref(), Some("b"));
+}
+
+#[test]
+pub fn lex_invalid_range() {
+    let script = "'é'";
+    for (start, end) in [(2, 3), (0, 2), (3, 1), (0, 10)] {
+        let error = Lexer::new_range(script, start, end).err().unwrap();
+        assert_eq!(
+            LexError::from_io(&error),
+            Some(&LexError::InvalidRange { span: start..end })
+        );
+    }
+}
+
+#[test]
+pub fn token_type_kind() {
+    assert_eq!(
+        TokenType::Keyword(Keyword::If).kind(),
+        TokenType::Keyword(Keyword::Else).kind()
+    );
+    assert_eq!(TokenType::Keyword(Keyword::If).kind(), TokenKind::Keyword);
+    assert_eq!(
+        TokenType::StringLit(StringType::Single).kind(),
+        TokenType::StringLit(StringType::NowDoc).kind()
+    );
+    assert_eq!(
+        TokenType::NumericalLit(Numeric::Float(0.5)).kind(),
+        TokenKind::NumericalLit
+    );
+    assert_ne!(TokenType::Identifier.kind(), TokenKind::Keyword);
+}
+
+#[test]
+pub fn variable_names() {
+    let tokens = lex("$foo = $$x . ${'y'} . $class;");
+    let name = |index| variable_name(&tokens, index);
+
+    assert_eq!(name(0), Some(VariableName::Named("foo")));
+    assert_eq!(name(3), Some(VariableName::Dynamic));
+    // the `$` of `$x`, within the variable variable.
+    assert_eq!(name(4), Some(VariableName::Named("x")));
+    assert_eq!(name(7), Some(VariableName::Dynamic));
+    assert_eq!(name(12), Some(VariableName::Named("class")));
+}
+
+#[test]
+pub fn variable_name_of_other_tokens() {
+    let tokens = lex("$a = foo; $ b;");
+
+    assert_eq!(variable_name(&tokens, 1), None);
+    assert_eq!(variable_name(&tokens, 3), None);
+    assert_eq!(variable_name(&tokens, 99), None);
+    // the name must directly follow the `$`.
+    assert_eq!(variable_name(&tokens, 5), None);
+}
+
+#[test]
+pub fn ends_operand() {
+    let tokens = lex("$a[0] = f(-1) + 'b' . true ?? null; T_X");
+    let ending: Vec<bool> = tokens.iter().map(|t| t.ends_operand()).collect();
+
+    assert_eq!(
+        ending,
+        vec![
+            false, // $
+            true,  // a
+            false, // [
+            true,  // 0
+            true,  // ]
+            false, // =
+            true,  // f
+            false, // (
+            false, // -
+            true,  // 1
+            true,  // )
+            false, // +
+            true,  // 'b'
+            false, // .
+            true,  // true
+            false, // ??
+            true,  // null
+            false, // ;
+            true,  // T_X
+        ]
+    );
+    for (token, ends) in tokens.iter().zip(ending) {
+        assert_eq!(token.0.ends_operand(), ends);
+    }
+}
+
+#[test]
+pub fn keywords_and_braces_do_not_end_operands() {
+    let tokens = lex("if ($a) { return -1; } new Foo;");
+
+    for token in &tokens {
+        if matches!(
+            token.kind(),
+            TokenType::Keyword(_) | TokenType::LeftBrace | TokenType::RightBrace
+        ) {
+            assert!(!token.ends_operand(), "{:?}", token);
+        }
+    }
+    assert!(tokens
+        .iter()
+        .any(|t| t.kind() == TokenType::Keyword(Keyword::Return)));
+}
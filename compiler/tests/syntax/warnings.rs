@@ -0,0 +1,202 @@
+use std::ops::Range;
+
+use compiler::syntax::lex::{
+    token::{Numeric, TokenType},
+    version::PhpVersion,
+    warning::WarningCode,
+    Lexer,
+};
+
+/// Lexes the whole script, returning the code and span of each warning collected.
+fn warnings(mut lexer: Lexer) -> Vec<(WarningCode, Range<usize>)> {
+    lexer.tokenize().expect("script should lex");
+
+    lexer
+        .warnings()
+        .iter()
+        .map(|w| (w.code, w.span.clone()))
+        .collect()
+}
+
+fn lex_warnings(script: &str) -> Vec<(WarningCode, Range<usize>)> {
+    warnings(Lexer::new(script).warn_deprecated())
+}
+
+#[test]
+pub fn asp_tags() {
+    let warnings = lex_warnings("<% echo $a; %>");
+
+    assert_eq!(
+        warnings,
+        vec![(WarningCode::AspTag, 0..2), (WarningCode::AspTag, 12..14)]
+    );
+}
+
+#[test]
+pub fn script_tag() {
+    let script = r#"<script language="php"> echo 1; </script>"#;
+
+    assert_eq!(lex_warnings(script), vec![(WarningCode::ScriptTag, 0..23)]);
+}
+
+#[test]
+pub fn curly_string_offset() {
+    let warnings = lex_warnings("$a = $str{0}; $b = $str[0];");
+
+    assert_eq!(warnings, vec![(WarningCode::CurlyStringOffset, 5..12)]);
+}
+
+#[test]
+pub fn not_collected_by_default() {
+    assert!(warnings(Lexer::new("<% echo $str{0}; %>")).is_empty());
+}
+
+#[test]
+pub fn doubled_silence_typo() {
+    let script = "@@x(); @@@y(); @z(); #[X]";
+    let tokens = Lexer::new(script).tokenize().unwrap();
+
+    assert_eq!(tokens[0].kind(), TokenType::Operator);
+    assert_eq!(tokens[1].kind(), TokenType::Operator);
+    assert_eq!(
+        warnings(Lexer::new(script).warn_typos()),
+        vec![
+            (WarningCode::DoubledSilence, 0..2),
+            (WarningCode::DoubledSilence, 7..9)
+        ]
+    );
+    assert_eq!(
+        WarningCode::DoubledSilence.hint(),
+        Some("did you mean the attribute syntax `#[...]`?")
+    );
+    assert_eq!(WarningCode::Tab.hint(), None);
+}
+
+#[test]
+pub fn typos_not_collected_by_default() {
+    assert!(warnings(Lexer::new("@@x();")).is_empty());
+}
+
+fn version_warnings(script: &str, version: PhpVersion) -> Vec<(WarningCode, Range<usize>)> {
+    warnings(Lexer::new(script).target(version).warn_deprecated())
+}
+
+#[test]
+pub fn curly_string_offset_by_version() {
+    let script = "echo $str{0};";
+
+    assert!(version_warnings(script, PhpVersion::PHP_7_4).is_empty());
+    assert_eq!(
+        version_warnings(script, PhpVersion::PHP_8_0),
+        vec![(WarningCode::CurlyStringOffset, 5..12)]
+    );
+    assert!(version_warnings("echo $str[0];", PhpVersion::PHP_8_0).is_empty());
+}
+
+#[test]
+pub fn property_hooks_are_not_curly_offsets() {
+    let script = "class A { public int $x{ get; } public int $y{set => 1;} public $z{&get {}} }";
+
+    assert!(version_warnings(script, PhpVersion::PHP_8_4).is_empty());
+    assert_eq!(
+        version_warnings("echo $str{getter};", PhpVersion::PHP_8_4),
+        vec![(WarningCode::CurlyStringOffset, 5..17)]
+    );
+}
+
+#[test]
+pub fn curly_string_offset_lexes_normally() {
+    let tokens = Lexer::new("$str{0}")
+        .target(PhpVersion::PHP_7_4)
+        .tokenize()
+        .unwrap();
+    let kinds: Vec<TokenType> = tokens.iter().map(|t| t.kind()).collect();
+
+    assert_eq!(
+        kinds,
+        vec![
+            TokenType::Variable,
+            TokenType::Identifier,
+            TokenType::LeftBrace,
+            TokenType::NumericalLit(Numeric::Int(0)),
+            TokenType::RightBrace,
+        ]
+    );
+}
+
+#[test]
+pub fn trailing_comma_array() {
+    let script = "$a = [1, 2, 3,];";
+
+    assert!(version_warnings(script, PhpVersion::PHP_7_3).is_empty());
+    assert!(version_warnings(script, PhpVersion::PHP_8_0).is_empty());
+}
+
+#[test]
+pub fn trailing_comma_call() {
+    assert!(version_warnings("foo($a, $b,);", PhpVersion::PHP_7_3).is_empty());
+}
+
+#[test]
+pub fn trailing_comma_parameters() {
+    let script = "function foo($a, $b,) {}";
+
+    assert_eq!(
+        version_warnings(script, PhpVersion::PHP_7_4),
+        vec![(WarningCode::TrailingComma, 19..20)]
+    );
+    assert!(version_warnings(script, PhpVersion::PHP_8_0).is_empty());
+}
+
+#[test]
+pub fn trailing_comma_by_reference_parameters() {
+    let script = "function &foo($a,) {} $f = fn &($b,) => $b;";
+
+    assert_eq!(
+        version_warnings(script, PhpVersion::PHP_7_4),
+        vec![
+            (WarningCode::TrailingComma, 16..17),
+            (WarningCode::TrailingComma, 34..35)
+        ]
+    );
+    assert!(version_warnings(script, PhpVersion::PHP_8_0).is_empty());
+}
+
+#[test]
+pub fn trailing_comma_not_collected_by_default() {
+    let lexer = Lexer::new("function foo($a,) {}").target(PhpVersion::PHP_7_4);
+
+    assert!(warnings(lexer).is_empty());
+}
+
+#[test]
+pub fn trailing_comma_in_parameter_default() {
+    // the trailing comma belongs to the array, not the parameter list.
+    let script = "function foo($a = [1, 2,]) {}";
+
+    assert!(version_warnings(script, PhpVersion::PHP_7_4).is_empty());
+}
+
+fn tab_warnings(lexer: Lexer) -> Vec<(WarningCode, Range<usize>)> {
+    warnings(lexer.forbid_tabs())
+}
+
+#[test]
+pub fn tabs_in_whitespace() {
+    let script = "if ($a) {\n\t\t$b = \"\t\";\t// \t\n}";
+
+    assert_eq!(
+        tab_warnings(Lexer::new(script)),
+        vec![
+            (WarningCode::Tab, 10..11),
+            (WarningCode::Tab, 11..12),
+            (WarningCode::Tab, 21..22),
+        ]
+    );
+    assert_eq!(tab_warnings(Lexer::new(script).split_whitespace()).len(), 3);
+}
+
+#[test]
+pub fn tabs_allowed_by_default() {
+    assert!(lex_warnings("\t$a = 1;").is_empty());
+}
@@ -0,0 +1,62 @@
+use compiler::syntax::lex::{
+    token::{Token, TokenType, LF},
+    Lexer,
+};
+
+fn split(script: &str) -> Vec<Token> {
+    Lexer::new(script).split_whitespace().tokenize().unwrap()
+}
+
+#[test]
+pub fn whitespace_is_one_token_by_default() {
+    let tokens = Lexer::new("$a;\n\n  $b;").tokenize().unwrap();
+
+    assert_eq!(tokens[3].kind(), TokenType::Whitespace);
+    assert_eq!(tokens[3].value().as_deref(), Some("\n\n  "));
+}
+
+#[test]
+pub fn split_line_breaks() {
+    let tokens = split("$a; \n  $b;");
+    let kinds: Vec<TokenType> = tokens.iter().map(|t| t.kind()).collect();
+
+    assert_eq!(
+        kinds[3..6],
+        [
+            TokenType::Whitespace,
+            TokenType::LF(LF::LF),
+            TokenType::Whitespace
+        ]
+    );
+    assert_eq!(tokens[3].value().as_deref(), Some(" "));
+    assert_eq!(tokens[4].value().as_deref(), Some("\n"));
+    assert_eq!(tokens[5].value().as_deref(), Some("  "));
+    assert_eq!(tokens[4].blank_lines(), 0);
+}
+
+#[test]
+pub fn blank_lines() {
+    let tokens = split("$a;\n\n  \n\t$b;");
+
+    assert_eq!(tokens[3].kind(), TokenType::LF(LF::LF));
+    assert_eq!(tokens[3].value().as_deref(), Some("\n\n  \n"));
+    assert_eq!(tokens[3].blank_lines(), 2);
+    assert_eq!(tokens[4].kind(), TokenType::Whitespace);
+    assert_eq!(tokens[4].value().as_deref(), Some("\t"));
+}
+
+#[test]
+pub fn crlf_line_breaks() {
+    let tokens = split("$a;\r\n\r\n$b;");
+
+    assert_eq!(tokens[3].kind(), TokenType::LF(LF::CRLF));
+    assert_eq!(tokens[3].blank_lines(), 1);
+    assert_eq!(tokens[4].kind(), TokenType::Variable);
+}
+
+#[test]
+pub fn line_breaks_are_not_significant() {
+    let tokens = split("$a\n.5");
+
+    assert_eq!(tokens[3].kind(), TokenType::Dot);
+}